@@ -0,0 +1,180 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::{ErrorKind, Result};
+
+const MIB: u64 = 1024 * 1024;
+const PARTIAL_EXTENSION: &str = "part";
+
+/// Limits of the directory where episodes are extracted from archives
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveLimits {
+    /// Entries bigger than this are not extracted, in MiB
+    pub max_entry_size: u64,
+    /// Least recently played files are removed when the cache grows
+    /// bigger than this, in MiB
+    pub max_cache_size: u64,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_entry_size: 4 * 1024,
+            max_cache_size: 10 * 1024,
+        }
+    }
+}
+
+/// File inside the ZIP archive
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ArchiveEntry {
+    pub(crate) archive: PathBuf,
+    pub(crate) name: String,
+    pub(crate) size: u64,
+}
+
+/// Whether the path is a ZIP archive which can be used as a chapter
+#[must_use]
+pub fn is_archive(path: &Path) -> bool {
+    let is_zip = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"));
+    is_zip && !path.is_dir()
+}
+
+/// List files of the archive, directories and hidden files are skipped
+pub fn archive_entries(path: &Path) -> Result<Vec<ArchiveEntry>> {
+    let mut archive = ZipArchive::new(fs::File::open(path)?)
+        .map_err(|source| ErrorKind::archive(path, source))?;
+    let mut entries = Vec::with_capacity(archive.len());
+    for index in 0..archive.len() {
+        let file = archive
+            .by_index_raw(index)
+            .map_err(|source| ErrorKind::archive(path, source))?;
+        let is_hidden = file
+            .name()
+            .split('/')
+            .any(|component| component.starts_with('.') || component == "__MACOSX");
+        if !file.is_file() || is_hidden {
+            continue;
+        }
+        entries.push(ArchiveEntry {
+            archive: path.to_path_buf(),
+            name: file.name().to_string(),
+            size: file.size(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Extract the entry into the cache directory unless it is already there
+///
+/// # Errors
+///
+/// Fails if the archive can't be read, the entry is bigger than
+/// [`ArchiveLimits::max_entry_size`] or the cache can't be written.
+pub async fn extract(
+    entry: ArchiveEntry,
+    cache_dir: PathBuf,
+    limits: ArchiveLimits,
+) -> Result<PathBuf> {
+    tokio::task::spawn_blocking(move || extract_blocking(&entry, &cache_dir, &limits))
+        .await
+        .map_err(io::Error::other)?
+}
+
+fn extract_blocking(
+    entry: &ArchiveEntry,
+    cache_dir: &Path,
+    limits: &ArchiveLimits,
+) -> Result<PathBuf> {
+    let max_entry_size = limits.max_entry_size.saturating_mul(MIB);
+    if entry.size > max_entry_size {
+        return Err(ErrorKind::archive_entry_too_big(
+            &entry.name,
+            entry.size,
+            max_entry_size,
+        ));
+    }
+    let target = cache_dir.join(cached_file_name(entry)?);
+    if fs::metadata(&target).is_ok_and(|metadata| metadata.len() == entry.size) {
+        fs::File::options()
+            .write(true)
+            .open(&target)?
+            .file()
+            .set_modified(SystemTime::now())?;
+        return Ok(target);
+    }
+    fs::create_dir_all(cache_dir)?;
+    let max_cache_size = limits.max_cache_size.saturating_mul(MIB);
+    clean_cache(cache_dir, max_cache_size.saturating_sub(entry.size))?;
+
+    let mut archive = ZipArchive::new(fs::File::open(&entry.archive)?)
+        .map_err(|source| ErrorKind::archive(&entry.archive, source))?;
+    let file = archive
+        .by_name(&entry.name)
+        .map_err(|source| ErrorKind::archive(&entry.archive, source))?;
+    let mut partial = target.clone().into_os_string();
+    partial.push(".");
+    partial.push(PARTIAL_EXTENSION);
+    let mut output = fs::File::create(&partial)?;
+    io::copy(&mut file.take(max_entry_size), &mut output)?;
+    fs::rename(&partial, &target)?;
+    Ok(target)
+}
+
+/// Name of the extracted file is unique for the archive, its modification
+/// time and the entry, but ends with the entry's file name for the player
+fn cached_file_name(entry: &ArchiveEntry) -> Result<String> {
+    let modified = fs::metadata(&entry.archive)?
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .unwrap_or_default()
+        .as_nanos();
+    let mut context = md5::Context::new();
+    context.consume(entry.archive.as_os_str().as_encoded_bytes());
+    // Separates the archive path from the entry name
+    context.consume([0]);
+    context.consume(entry.name.as_bytes());
+    context.consume(modified.to_le_bytes());
+    let file_name = Path::new(&entry.name)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    Ok(format!("{:x}-{file_name}", context.finalize()))
+}
+
+/// Remove least recently used files until the cache fits into `max_size`
+fn clean_cache(cache_dir: &Path, max_size: u64) -> Result<()> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(cache_dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_file() {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((modified, metadata.len(), entry.path()));
+        }
+    }
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    files.sort();
+    for (_, len, path) in files {
+        if total <= max_size {
+            break;
+        }
+        fs::remove_file(&path)?;
+        total -= len;
+    }
+    Ok(())
+}
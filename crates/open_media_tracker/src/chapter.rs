@@ -0,0 +1,122 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use expand_tilde::ExpandTilde;
+
+use crate::episode::{read_episodes, total_duration};
+use crate::utils;
+use crate::{ChapterMatcher, Result, ScanOptions};
+
+/// Subdirectory of a series root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    path: PathBuf,
+    episodes_count: usize,
+    duration: Option<Duration>,
+}
+
+impl Chapter {
+    /// Directory of the chapter
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Name of the chapter directory
+    #[must_use]
+    pub fn name(&self) -> Cow<'_, str> {
+        self.path.file_name().unwrap_or_default().to_string_lossy()
+    }
+
+    /// How many episodes the chapter has
+    #[must_use]
+    pub const fn episodes_count(&self) -> usize {
+        self.episodes_count
+    }
+
+    /// Sum of the known episode durations
+    #[must_use]
+    pub const fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+}
+
+/// Read subdirectories of the series root which contain episodes and
+/// matched by `matcher`
+pub async fn read_chapters(
+    series_path: impl AsRef<Path>,
+    options: &ScanOptions,
+    matcher: &ChapterMatcher,
+) -> Result<Vec<Chapter>> {
+    let series_path = series_path.as_ref().expand_tilde()?;
+    let mut paths = utils::read_dir_with_filter(&*series_path, Path::is_dir).await?;
+    paths.sort_by(|a, b| options.sort_order.compare(a, b));
+    let mut chapters = Vec::with_capacity(paths.len());
+    for path in paths {
+        let dir_name = path.file_name().unwrap_or_default();
+        if !matcher.is_match(&dir_name.to_string_lossy()) {
+            continue;
+        }
+        let Ok(episodes) = read_episodes(&path, options).await else {
+            continue;
+        };
+        if episodes.is_empty() {
+            continue;
+        }
+        chapters.push(Chapter {
+            path,
+            episodes_count: episodes.len(),
+            duration: total_duration(episodes.iter()),
+        });
+    }
+    Ok(chapters)
+}
+
+/// Number of the chapter with the given path. The path may start with `~`
+#[must_use]
+pub fn chapter_number(chapters: &[Chapter], path: &Path) -> Option<u8> {
+    let path = path.expand_tilde().ok()?;
+    let position = chapters.iter().position(|chapter| chapter.path == path)?;
+    u8::try_from(position + 1).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter(path: PathBuf) -> Chapter {
+        Chapter {
+            path,
+            episodes_count: 1,
+            duration: None,
+        }
+    }
+
+    #[test]
+    fn chapter_path_with_tilde_is_found() {
+        let home = Path::new("~").expand_tilde().unwrap().into_owned();
+        let chapters = [
+            chapter(home.join("Show/Season 1")),
+            chapter(home.join("Show/Season 2")),
+        ];
+        assert_eq!(
+            chapter_number(&chapters, Path::new("~/Show/Season 2")),
+            Some(2)
+        );
+        assert_eq!(
+            chapter_number(&chapters, &home.join("Show/Season 1")),
+            Some(1)
+        );
+        assert_eq!(
+            chapter_number(&chapters, Path::new("~/Show/Season 3")),
+            None
+        );
+    }
+}
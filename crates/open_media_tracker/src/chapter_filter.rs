@@ -0,0 +1,125 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use globset::{GlobBuilder, GlobMatcher};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::{ErrorKind, Result};
+
+/// Prefix of the patterns which must be treated as regular expressions
+const REGEX_PREFIX: &str = "re:";
+
+const DEFAULT_EXCLUDE: &[&str] = &[
+    "Extras",
+    "Featurettes",
+    "Specials",
+    "Subs",
+    "Subtitles",
+    "Sample*",
+    "Trailers",
+    "Behind the Scenes",
+    "Deleted Scenes",
+    "Interviews",
+];
+
+/// Patterns which chapter directory names must (include) or must not
+/// (exclude) match. Patterns are case insensitive globs, or regular
+/// expressions if starts with `re:`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChapterFilter {
+    /// Directories which count as chapters, empty means any
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Directories which never count as chapters
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl ChapterFilter {
+    /// Filter which skips extras, specials, subtitles and so on
+    pub fn with_default_exclude() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: DEFAULT_EXCLUDE.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    /// Check that every pattern compiles
+    ///
+    /// # Errors
+    ///
+    /// Fails on the first invalid pattern.
+    pub fn validate(&self) -> Result<()> {
+        ChapterMatcher::new([self]).map(|_| ())
+    }
+}
+
+/// Compiled [`ChapterFilter`]s
+#[derive(Debug, Clone)]
+pub struct ChapterMatcher {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl ChapterMatcher {
+    /// Combine several filters, for example global and media's ones
+    ///
+    /// # Errors
+    ///
+    /// Fails on the first invalid pattern.
+    pub fn new<'a>(filters: impl IntoIterator<Item = &'a ChapterFilter>) -> Result<Self> {
+        let mut include = Vec::new();
+        let mut exclude = Vec::new();
+        for filter in filters {
+            for pattern in &filter.include {
+                include.push(Pattern::new(pattern)?);
+            }
+            for pattern in &filter.exclude {
+                exclude.push(Pattern::new(pattern)?);
+            }
+        }
+        Ok(Self { include, exclude })
+    }
+
+    /// Whether the directory is a chapter: matched by any include pattern,
+    /// if there are some, and by no exclude pattern
+    #[must_use]
+    pub fn is_match(&self, dir_name: &str) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|p| p.is_match(dir_name));
+        included && !self.exclude.iter().any(|p| p.is_match(dir_name))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Pattern {
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn new(pattern: &str) -> Result<Self> {
+        if let Some(regex) = pattern.strip_prefix(REGEX_PREFIX) {
+            let regex = RegexBuilder::new(regex)
+                .case_insensitive(true)
+                .build()
+                .map_err(|err| ErrorKind::invalid_pattern(pattern, err.to_string()))?;
+            return Ok(Self::Regex(regex));
+        }
+        let glob = GlobBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|err| ErrorKind::invalid_pattern(pattern, err.to_string()))?;
+        Ok(Self::Glob(glob.compile_matcher()))
+    }
+
+    fn is_match(&self, dir_name: &str) -> bool {
+        match self {
+            Self::Glob(glob) => glob.is_match(dir_name),
+            Self::Regex(regex) => regex.is_match(dir_name),
+        }
+    }
+}
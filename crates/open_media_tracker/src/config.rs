@@ -0,0 +1,107 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use etcetera::{BaseStrategy, HomeDirError};
+use fs_err as fs;
+
+use crate::error::Result;
+
+const DATA_DIR_NAME: &str = "open_media_tracker";
+const ARCHIVE_CACHE_DIR_NAME: &str = "archives";
+const THUMBNAILS_DIR_NAME: &str = "thumbnails";
+
+/// Directories where media, settings and caches are stored
+#[derive(Debug)]
+#[allow(clippy::struct_field_names)]
+pub struct Config {
+    /// Media files
+    pub data_dir: PathBuf,
+    /// Settings file
+    pub config_dir: PathBuf,
+    /// Files extracted from archives
+    pub archive_cache: PathBuf,
+    /// Thumbnails of media covers
+    pub thumbnails: PathBuf,
+}
+
+impl Config {
+    /// Find user's directories and create the missing ones
+    ///
+    /// # Errors
+    ///
+    /// Fails if the user's directories are unknown or can't be created.
+    pub fn read() -> Result<Self> {
+        let user_dirs = etcetera::choose_base_strategy().map_err(UserDataDirNotFoundError::new)?;
+        let user_data_dir = user_dirs.data_dir();
+        let data_dir = user_data_dir.join(DATA_DIR_NAME);
+        if !data_dir.exists() {
+            fs::create_dir(&data_dir)?;
+        }
+        let config_dir = user_dirs.config_dir().join(DATA_DIR_NAME);
+        if !config_dir.exists() {
+            fs::create_dir_all(&config_dir)?;
+        }
+        let cache_dir = user_dirs.cache_dir().join(DATA_DIR_NAME);
+        let archive_cache = cache_dir.join(ARCHIVE_CACHE_DIR_NAME);
+        let thumbnails = cache_dir.join(THUMBNAILS_DIR_NAME);
+        Ok(Self {
+            data_dir,
+            config_dir,
+            archive_cache,
+            thumbnails,
+        })
+    }
+
+    /// Default directories used when the real ones can't be determined,
+    /// nothing is created
+    #[must_use]
+    pub fn placeholder() -> Self {
+        let user_dirs = etcetera::choose_base_strategy().ok();
+        Self {
+            data_dir: user_dirs
+                .as_ref()
+                .map(|d| d.data_dir().join(DATA_DIR_NAME))
+                .unwrap_or_default(),
+            config_dir: user_dirs
+                .as_ref()
+                .map(|d| d.config_dir().join(DATA_DIR_NAME))
+                .unwrap_or_default(),
+            archive_cache: user_dirs
+                .as_ref()
+                .map(|d| {
+                    d.cache_dir()
+                        .join(DATA_DIR_NAME)
+                        .join(ARCHIVE_CACHE_DIR_NAME)
+                })
+                .unwrap_or_default(),
+            thumbnails: user_dirs
+                .as_ref()
+                .map(|d| d.cache_dir().join(DATA_DIR_NAME).join(THUMBNAILS_DIR_NAME))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Path of the JSON file of the media
+    pub fn path_to_media(&self, file_name: impl AsRef<Path>) -> PathBuf {
+        self.data_dir.join(file_name)
+    }
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Failed to found user's data directory: {source}")]
+pub struct UserDataDirNotFoundError {
+    source: Arc<HomeDirError>,
+}
+
+impl UserDataDirNotFoundError {
+    fn new(source: HomeDirError) -> Self {
+        let source = source.into();
+        Self { source }
+    }
+}
@@ -0,0 +1,194 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Cover images of media and their thumbnails cached in the cache directory
+
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use expand_tilde::ExpandTilde;
+use fs_err as fs;
+use image::{DynamicImage, ImageFormat, ImageReader};
+
+use crate::{Config, EpisodeList, ErrorKind, MediaHandler, Result, probe};
+
+const THUMBNAIL_WIDTH: u32 = 200;
+const THUMBNAIL_HEIGHT: u32 = 300;
+
+/// Image file shown as the cover of the media
+#[derive(Debug, Clone)]
+enum Cover {
+    File(PathBuf),
+    /// Art embedded into the episode
+    Embedded(PathBuf),
+}
+
+impl Cover {
+    fn path(&self) -> &Path {
+        match self {
+            Self::File(path) | Self::Embedded(path) => path,
+        }
+    }
+
+    fn decode(&self) -> Result<Option<DynamicImage>> {
+        let image = match self {
+            Self::File(path) => ImageReader::open(path)?.with_guessed_format()?.decode(),
+            Self::Embedded(path) => {
+                let Some(bytes) = probe::embedded_cover(path) else {
+                    return Ok(None);
+                };
+                image::load_from_memory(&bytes)
+            }
+        };
+        image
+            .map(Some)
+            .map_err(|err| ErrorKind::thumbnail(self.path(), err.to_string()))
+    }
+}
+
+/// Thumbnail of the media cover, made if the cache has none. The cover
+/// chosen manually is preferred to the folder artwork, which is preferred to
+/// the art embedded into the first of `episodes`
+///
+/// # Errors
+///
+/// Fails if the cover can't be read or decoded.
+pub async fn thumbnail(
+    media: &MediaHandler,
+    episodes: &EpisodeList,
+    config: &Config,
+) -> Result<Option<PathBuf>> {
+    let Some(cover) = find_cover(media, episodes).await? else {
+        return Ok(None);
+    };
+    let modified = fs::tokio::metadata(cover.path()).await?.modified()?;
+    let dir = config.thumbnails.join(media_dir_name(media.name()));
+    let thumbnail = dir.join(thumbnail_name(cover.path(), modified));
+    if thumbnail.is_file() {
+        return Ok(Some(thumbnail));
+    }
+    tokio::task::spawn_blocking(move || {
+        let Some(image) = cover.decode()? else {
+            return Ok(None);
+        };
+        // The media keeps the thumbnail of its latest cover only
+        if dir.is_dir() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+        image
+            .thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT)
+            .save_with_format(&thumbnail, ImageFormat::Png)
+            .map_err(|err| ErrorKind::thumbnail(&thumbnail, err.to_string()))?;
+        Ok(Some(thumbnail))
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Remove thumbnails of media which isn't in the list anymore. Thumbnails
+/// of the listed media are kept even if their covers failed to load
+///
+/// # Errors
+///
+/// Fails if the cache directory can't be read.
+pub fn remove_unused_thumbnails<'a>(
+    config: &Config,
+    media_names: impl IntoIterator<Item = &'a str>,
+) -> Result<()> {
+    if !config.thumbnails.is_dir() {
+        return Ok(());
+    }
+    let used: HashSet<_> = media_names.into_iter().map(media_dir_name).collect();
+    for entry in fs::read_dir(&config.thumbnails)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path
+            .file_name()
+            .and_then(OsStr::to_str)
+            .is_some_and(|name| used.contains(name))
+        {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            fs::remove_dir_all(&path)?;
+        } else {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+async fn find_cover(media: &MediaHandler, episodes: &EpisodeList) -> Result<Option<Cover>> {
+    if let Some(path) = media.cover() {
+        return Ok(Some(Cover::File(path.expand_tilde()?.into_owned())));
+    }
+    let poster = match &media.metadata().poster {
+        Some(poster) => Some(poster.clone()),
+        // Media saved before metadata was read
+        None => media.load_metadata().await.poster,
+    };
+    if let Some(poster) = poster {
+        return Ok(Some(Cover::File(poster)));
+    }
+    let first = episodes
+        .iter()
+        .find(|episode| episode.archive_entry().is_none());
+    Ok(first.map(|episode| Cover::Embedded(episode.path().to_path_buf())))
+}
+
+/// Thumbnails of each media are kept in a directory named by MD5 of the
+/// media name, as the name may contain any characters
+fn media_dir_name(name: &str) -> String {
+    format!("{:x}", md5::compute(name))
+}
+
+/// Thumbnails are named by MD5 of the cover path and its modification time,
+/// so a changed cover gets a new thumbnail
+fn thumbnail_name(path: &Path, modified: SystemTime) -> String {
+    let modified = modified
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let mut context = md5::Context::new();
+    context.consume(path.as_os_str().as_encoded_bytes());
+    context.consume(modified.to_le_bytes());
+    format!("{:x}.png", context.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thumbnails_of_listed_media_are_kept() {
+        let dir = std::env::temp_dir().join(format!("omt-thumbnails-{}", std::process::id()));
+        let config = Config {
+            data_dir: dir.clone(),
+            config_dir: dir.clone(),
+            archive_cache: dir.clone(),
+            thumbnails: dir.join("thumbnails"),
+        };
+        let kept = config.thumbnails.join(media_dir_name("Show"));
+        let removed = config.thumbnails.join(media_dir_name("Removed"));
+        for media_dir in [&kept, &removed] {
+            fs::create_dir_all(media_dir).unwrap();
+            fs::write(media_dir.join("cover.png"), []).unwrap();
+        }
+        // Thumbnails cached before they were kept by media
+        let flat = config.thumbnails.join("cover.png");
+        fs::write(&flat, []).unwrap();
+
+        remove_unused_thumbnails(&config, ["Show"]).unwrap();
+        assert!(kept.join("cover.png").is_file());
+        assert!(!removed.exists());
+        assert!(!flat.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,114 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::probe::EmbeddedChapter;
+
+const FRAMES_PER_SEC: u64 = 75;
+
+/// Tracks of one file listed in the CUE sheet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CueFile {
+    pub(crate) file_name: String,
+    pub(crate) tracks: Vec<EmbeddedChapter>,
+}
+
+impl CueFile {
+    /// CUE sheets often refer to the `.wav` file while the rip was encoded
+    /// into another format later, so the name without extension is enough
+    pub fn refers_to(&self, path: &Path) -> bool {
+        let cue_path = Path::new(&self.file_name);
+        path.file_name() == cue_path.file_name() || path.file_stem() == cue_path.file_stem()
+    }
+}
+
+pub fn is_cue_sheet(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("cue"))
+}
+
+/// Parse `FILE`, `TRACK`, `TITLE` and `INDEX 01` commands of the CUE sheet
+pub fn parse_cue_sheet(content: &str) -> Vec<CueFile> {
+    let mut files: Vec<CueFile> = Vec::new();
+    let mut track: Option<(u32, String)> = None;
+    for line in content.trim_start_matches('\u{feff}').lines() {
+        let args = arguments(line);
+        let Some((command, args)) = args.split_first() else {
+            continue;
+        };
+        match (command.to_ascii_uppercase().as_str(), args) {
+            ("FILE", [file_name, ..]) => {
+                track = None;
+                files.push(CueFile {
+                    file_name: file_name.clone(),
+                    tracks: Vec::new(),
+                });
+            }
+            ("TRACK", [number, ..]) => {
+                let number = number.parse().unwrap_or_default();
+                track = Some((number, String::new()));
+            }
+            ("TITLE", [title, ..]) => {
+                if let Some((_, track_title)) = &mut track {
+                    track_title.clone_from(title);
+                }
+            }
+            ("INDEX", [index, time, ..]) if index.parse() == Ok(1) => {
+                let (Some(file), Some((number, title)), Some(start)) =
+                    (files.last_mut(), &track, parse_time(time))
+                else {
+                    continue;
+                };
+                let title = if title.is_empty() {
+                    format!("Track {number:02}")
+                } else {
+                    title.clone()
+                };
+                file.tracks.push(EmbeddedChapter { title, start });
+            }
+            _ => {}
+        }
+    }
+    files.retain(|file| !file.tracks.is_empty());
+    for file in &mut files {
+        file.tracks.sort_by_key(|track| track.start);
+    }
+    files
+}
+
+/// Parse `mm:ss:ff` where `ff` is 1/75 of a second
+fn parse_time(time: &str) -> Option<Duration> {
+    let mut parts = time.split(':').map(str::parse::<u64>);
+    let (Some(Ok(minutes)), Some(Ok(seconds)), Some(Ok(frames)), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    let secs = Duration::from_secs(minutes * 60 + seconds);
+    let frames = Duration::from_millis(frames * 1000 / FRAMES_PER_SEC);
+    Some(secs + frames)
+}
+
+/// Split line into words, quoted words may contain spaces
+fn arguments(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut rest = line.trim();
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            args.push(quoted[..end].to_string());
+            rest = quoted.get(end + 1..).unwrap_or_default();
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            args.push(rest[..end].to_string());
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+    args
+}
@@ -0,0 +1,529 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use derive_more::{Deref, Display};
+use expand_tilde::ExpandTilde;
+use fs_err as fs;
+
+use crate::archive::{ArchiveEntry, archive_entries, is_archive};
+use crate::cue_sheet::{is_cue_sheet, parse_cue_sheet};
+use crate::episode_anchor::episode_number;
+use crate::media_detector::{SkipReason, detect_media, detect_media_by_extension};
+use crate::nfo::{is_nfo, parse_nfo};
+use crate::playlist::{is_playlist, is_remote, read_playlist};
+use crate::probe::{EmbeddedChapter, probe_media};
+use crate::sidecar::{SidecarKind, belongs_to, is_audio, is_subtitle};
+use crate::utils::read_dir_in_tree;
+use crate::{EpisodeNumber, ErrorKind, ScanOptions, Sidecar};
+
+/// Video or audio file of the chapter, or a part of it
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Episode {
+    path: PathBuf,
+    number: Option<EpisodeNumber>,
+    size: Option<u64>,
+    duration: Option<Duration>,
+    /// Offset of the chapter embedded into the file
+    start: Option<Duration>,
+    title: Option<String>,
+    archive_entry: Option<ArchiveEntry>,
+    sidecars: Vec<Sidecar>,
+}
+
+impl Episode {
+    /// Episode of the whole file with the number parsed from its name
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let number = path
+            .file_stem()
+            .and_then(|stem| EpisodeNumber::parse(&stem.to_string_lossy()));
+
+        Self {
+            path,
+            number,
+            size: None,
+            duration: None,
+            start: None,
+            title: None,
+            archive_entry: None,
+            sidecars: Vec::new(),
+        }
+    }
+
+    /// Copy of the episode played from the file extracted from the archive
+    #[must_use]
+    pub fn extracted(&self, path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            archive_entry: None,
+            ..self.clone()
+        }
+    }
+
+    /// Split the file into episodes for every embedded chapter
+    fn split(self, chapters: &[EmbeddedChapter]) -> impl Iterator<Item = Self> {
+        let ends = chapters
+            .iter()
+            .skip(1)
+            .map(|chapter| Some(chapter.start))
+            .chain([self.duration]);
+        chapters.iter().zip(ends).map(move |(chapter, end)| Self {
+            number: None,
+            duration: end.and_then(|end| end.checked_sub(chapter.start)),
+            start: Some(chapter.start),
+            title: Some(chapter.title.clone()).filter(|title| !title.is_empty()),
+            ..self.clone()
+        })
+    }
+
+    /// File of the episode
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Name of the episode file
+    #[must_use]
+    pub fn file_name(&self) -> Cow<'_, str> {
+        self.path.file_name().unwrap_or_default().to_string_lossy()
+    }
+
+    /// Title of the embedded chapter, of the playlist entry or from the NFO
+    /// file of the episode. The file name without extension otherwise
+    pub fn name(&self) -> Cow<'_, str> {
+        self.title.as_deref().map_or_else(
+            || self.path.file_stem().unwrap_or_default().to_string_lossy(),
+            Cow::Borrowed,
+        )
+    }
+
+    /// Season and episode numbers parsed from the file name
+    #[must_use]
+    pub const fn number(&self) -> Option<EpisodeNumber> {
+        self.number
+    }
+
+    /// Size of the file in bytes if known
+    #[must_use]
+    pub const fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// Duration read from the file headers
+    #[must_use]
+    pub const fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    /// Where the playback starts if the episode is a chapter inside the file
+    #[must_use]
+    pub const fn start(&self) -> Option<Duration> {
+        self.start
+    }
+
+    /// File inside the ZIP archive which is extracted before watching
+    #[must_use]
+    pub const fn archive_entry(&self) -> Option<&ArchiveEntry> {
+        self.archive_entry.as_ref()
+    }
+
+    /// Subtitles and external audio tracks named after the episode
+    #[must_use]
+    pub fn sidecars(&self) -> &[Sidecar] {
+        &self.sidecars
+    }
+}
+
+/// Find episode by its number.
+///
+/// If the file names are numbered the episode with the matching parsed number
+/// is returned, so a missing or extra file does not shift the rest of the list.
+/// Otherwise the number is the position in the sorted list.
+pub fn find_episode(episodes: &[Episode], number: u8) -> Option<&Episode> {
+    if is_numbered(episodes) {
+        episodes.iter().find(|episode| {
+            episode
+                .number
+                .is_some_and(|n| n.episode == u16::from(number))
+        })
+    } else {
+        episodes.get(usize::from(number).checked_sub(1)?)
+    }
+}
+
+/// Number of the first episode on the disk. Chapters numbered from the
+/// start of the series begin with a number other than 1
+pub fn first_episode_number(episodes: &[Episode]) -> u8 {
+    if !is_numbered(episodes) {
+        return 1;
+    }
+    episodes
+        .iter()
+        .filter_map(|episode| episode.number)
+        .map(|number| number.episode)
+        .min()
+        .and_then(|number| u8::try_from(number).ok())
+        .unwrap_or(1)
+}
+
+/// Number of the last episode on the disk
+pub fn last_episode_number(episodes: &[Episode]) -> Option<usize> {
+    if episodes.is_empty() {
+        return None;
+    }
+    if !is_numbered(episodes) {
+        return Some(episodes.len());
+    }
+    episodes
+        .iter()
+        .filter_map(|episode| episode.number)
+        .map(|number| usize::from(number.episode))
+        .max()
+}
+
+/// Whether episodes are found by the numbers parsed from the file names.
+/// Numbers are trusted only if they belong to one season and don't repeat,
+/// otherwise the position in the list is used
+pub fn is_numbered(episodes: &[Episode]) -> bool {
+    let mut numbers = episodes.iter().filter_map(|episode| episode.number);
+    let Some(first) = numbers.next() else {
+        return false;
+    };
+    let mut seen = HashSet::from([first.episode]);
+    numbers.all(|number| number.season == first.season && seen.insert(number.episode))
+}
+
+/// Sum of the known episode durations. `None` if no duration is known
+pub fn total_duration<'a>(episodes: impl IntoIterator<Item = &'a Episode>) -> Option<Duration> {
+    episodes
+        .into_iter()
+        .filter_map(Episode::duration)
+        .reduce(|total, duration| total + duration)
+}
+
+/// Duration of the episode with the given number and all episodes after it
+pub fn remaining_duration(episodes: &[Episode], number: u8) -> Option<Duration> {
+    let current = find_episode(episodes, number)?;
+    let position = episodes.iter().position(|episode| episode == current)?;
+    total_duration(&episodes[position..])
+}
+
+/// File in the chapter directory which is not treated as an episode
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+#[display("{file_name}: {reason}")]
+pub struct SkippedFile {
+    pub(crate) file_name: String,
+    pub(crate) reason: SkipReason,
+}
+
+impl SkippedFile {
+    fn new(path: &Path, reason: SkipReason) -> Self {
+        let file_name = path.file_name().unwrap_or_default();
+        Self {
+            file_name: file_name.to_string_lossy().into_owned(),
+            reason,
+        }
+    }
+}
+
+/// Episodes of the chapter and files which was skipped
+#[derive(Debug, Clone, Default, Deref)]
+pub struct EpisodeList {
+    #[deref]
+    episodes: Vec<Episode>,
+    skipped: Vec<SkippedFile>,
+}
+
+impl EpisodeList {
+    /// Files of the chapter which are not episodes and the reasons why
+    #[must_use]
+    pub fn skipped(&self) -> &[SkippedFile] {
+        &self.skipped
+    }
+
+    /// Find episode by its number. If the file names are numbered the
+    /// parsed number is used, otherwise the position in the list
+    #[must_use]
+    pub fn find(&self, number: u8) -> Option<&Episode> {
+        find_episode(&self.episodes, number)
+    }
+
+    /// Number of the first episode on the disk, 1 unless the episodes are
+    /// numbered from the start of the series
+    #[must_use]
+    pub fn first_number(&self) -> u8 {
+        first_episode_number(&self.episodes)
+    }
+
+    /// Number of the last episode on the disk
+    #[must_use]
+    pub fn last_number(&self) -> Option<usize> {
+        last_episode_number(&self.episodes)
+    }
+
+    /// Duration from the beginning of the episode to the end of the chapter
+    #[must_use]
+    pub fn remaining_duration(&self, number: u8) -> Option<Duration> {
+        remaining_duration(&self.episodes, number)
+    }
+
+    /// Episode which plays the file at the position along with its number.
+    /// Tracks of a CUE sheet share the file, the last one started before the
+    /// position wins
+    #[must_use]
+    pub fn find_playing(&self, path: &Path, position: Duration) -> Option<(u8, &Episode)> {
+        let index = self.episodes.iter().rposition(|episode| {
+            episode.path() == path && episode.start().unwrap_or_default() <= position
+        })?;
+        let number = episode_number(&self.episodes, index)?;
+        Some((number, &self.episodes[index]))
+    }
+}
+
+/// Find episodes of the chapter along with the skipped files
+///
+/// # Errors
+///
+/// Fails if the directory can't be read.
+pub async fn read_episodes(
+    path: impl AsRef<Path>,
+    options: &ScanOptions,
+) -> Result<EpisodeList, ErrorKind> {
+    let media_path = path.as_ref().expand_tilde()?;
+    if is_playlist(&media_path) {
+        return read_playlist_episodes(&media_path).await;
+    }
+    if is_archive(&media_path) {
+        return read_archive_episodes(&media_path, options);
+    }
+    let series_path = options.series_path.as_deref();
+    let file_paths = read_dir_in_tree(&*media_path, series_path, Path::is_file).await?;
+    let (subtitle_paths, file_paths): (Vec<_>, Vec<_>) =
+        file_paths.into_iter().partition(|path| is_subtitle(path));
+    let (cue_paths, file_paths): (Vec<_>, Vec<_>) =
+        file_paths.into_iter().partition(|path| is_cue_sheet(path));
+    let (nfo_paths, file_paths): (Vec<_>, Vec<_>) =
+        file_paths.into_iter().partition(|path| is_nfo(path));
+    let mut episodes = Vec::with_capacity(file_paths.len());
+    let mut embedded_chapters = HashMap::new();
+    let mut skipped = Vec::new();
+    for path in file_paths {
+        if let Err(reason) = detect_media(&path, &options.media_extensions).await {
+            skipped.push(SkippedFile::new(&path, reason));
+            continue;
+        }
+        let mut episode = Episode::new(path);
+        let metadata = fs::tokio::metadata(&episode.path).await.ok();
+        episode.size = metadata.as_ref().map(std::fs::Metadata::len);
+        let modified = metadata.and_then(|metadata| metadata.modified().ok());
+        let info = probe_media(episode.path.clone(), modified).await;
+        episode.duration = info.duration;
+        if is_audio(&episode.path) && info.chapters.len() > 1 {
+            embedded_chapters.insert(episode.path.clone(), info.chapters);
+        }
+        episodes.push(episode);
+    }
+    attach_audio_tracks(&mut episodes);
+    for path in subtitle_paths {
+        match find_owner(episodes.iter_mut(), &path) {
+            Some(owner) => owner
+                .sidecars
+                .push(Sidecar::new(path, SidecarKind::Subtitle)),
+            None => skipped.push(SkippedFile::new(&path, SkipReason::NoEpisode)),
+        }
+    }
+    for episode in &mut episodes {
+        episode.sidecars.sort();
+    }
+    // NFO files of the show and the season are not named after episodes
+    for path in nfo_paths {
+        if let Some(owner) = episodes
+            .iter_mut()
+            .find(|episode| episode.path.file_stem() == path.file_stem())
+            && let Ok(content) = fs::tokio::read_to_string(&path).await
+            && let Some(nfo) = parse_nfo(&content)
+        {
+            owner.title = nfo.title;
+        }
+    }
+    for path in cue_paths {
+        if let Err(reason) = read_cue_sheet(&path, &episodes, &mut embedded_chapters).await {
+            skipped.push(SkippedFile::new(&path, reason));
+        }
+    }
+    let mut episodes: Vec<_> = episodes
+        .into_iter()
+        .flat_map(|episode| match embedded_chapters.remove(&episode.path) {
+            Some(chapters) => episode.split(&chapters).collect(),
+            None => vec![episode],
+        })
+        .collect();
+    // Sorting is stable, so chapters of one file keep their order
+    episodes.sort_by(|a, b| options.sort_order.compare(a.path(), b.path()));
+    Ok(EpisodeList { episodes, skipped })
+}
+
+/// Episodes in the order of the playlist entries. Local files are probed
+/// like files in a directory, streams keep what the playlist says about them
+async fn read_playlist_episodes(path: &Path) -> Result<EpisodeList, ErrorKind> {
+    let mut episodes = Vec::new();
+    let mut skipped = Vec::new();
+    for entry in read_playlist(path).await? {
+        let mut episode = Episode::new(&entry.location);
+        episode.number = None;
+        episode.title = entry.title;
+        if !is_remote(&entry.location) {
+            let Ok(metadata) = fs::tokio::metadata(&entry.location).await else {
+                skipped.push(SkippedFile::new(&entry.location, SkipReason::NotFound));
+                continue;
+            };
+            episode.size = Some(metadata.len());
+            let modified = metadata.modified().ok();
+            episode.duration = probe_media(entry.location.clone(), modified).await.duration;
+        }
+        episode.duration = episode.duration.or(entry.duration);
+        episodes.push(episode);
+    }
+    Ok(EpisodeList { episodes, skipped })
+}
+
+/// Media files inside the ZIP archive. Content of the entries is not read,
+/// so they are recognized by the extension only
+fn read_archive_episodes(path: &Path, options: &ScanOptions) -> Result<EpisodeList, ErrorKind> {
+    let mut episodes = Vec::new();
+    let mut skipped = Vec::new();
+    for entry in archive_entries(path)? {
+        let entry_path = path.join(&entry.name);
+        let detected = detect_media_by_extension(&entry_path, &options.media_extensions)
+            .unwrap_or(Err(SkipReason::UnknownContent));
+        if let Err(reason) = detected {
+            skipped.push(SkippedFile::new(&entry_path, reason));
+            continue;
+        }
+        let mut episode = Episode::new(entry_path);
+        episode.size = Some(entry.size);
+        episode.archive_entry = Some(entry);
+        episodes.push(episode);
+    }
+    episodes.sort_by(|a, b| options.sort_order.compare(a.path(), b.path()));
+    Ok(EpisodeList { episodes, skipped })
+}
+
+/// Tracks listed in the CUE sheet replace chapters embedded into the file
+async fn read_cue_sheet(
+    path: &Path,
+    episodes: &[Episode],
+    chapters: &mut HashMap<PathBuf, Vec<EmbeddedChapter>>,
+) -> Result<(), SkipReason> {
+    let content = fs::tokio::read(path)
+        .await
+        .map_err(|err| SkipReason::Unreadable {
+            message: err.to_string(),
+        })?;
+    let mut found = false;
+    for file in parse_cue_sheet(&String::from_utf8_lossy(&content)) {
+        if let Some(episode) = episodes
+            .iter()
+            .find(|episode| file.refers_to(&episode.path))
+        {
+            chapters.insert(episode.path.clone(), file.tracks);
+            found = true;
+        }
+    }
+    if found {
+        Ok(())
+    } else {
+        Err(SkipReason::NoEpisode)
+    }
+}
+
+/// Move audio files named after a video episode to its sidecars
+fn attach_audio_tracks(episodes: &mut Vec<Episode>) {
+    let is_track = |audio: &Episode, episodes: &[Episode]| {
+        is_audio(&audio.path)
+            && episodes
+                .iter()
+                .any(|video| !is_audio(&video.path) && belongs_to(&audio.path, &video.path))
+    };
+    let (tracks, rest): (Vec<_>, Vec<_>) = episodes
+        .iter()
+        .cloned()
+        .partition(|episode| is_track(episode, episodes));
+    *episodes = rest;
+    for track in tracks {
+        let videos = episodes.iter_mut().filter(|video| !is_audio(&video.path));
+        if let Some(owner) = find_owner(videos, &track.path) {
+            owner
+                .sidecars
+                .push(Sidecar::new(track.path, SidecarKind::Audio));
+        }
+    }
+}
+
+/// Episode the sidecar file is named after. The longest name wins, so
+/// `Ep 1.5.srt` goes to `Ep 1.5.mkv` rather than to `Ep 1.mkv`
+fn find_owner<'a>(
+    episodes: impl Iterator<Item = &'a mut Episode>,
+    path: &Path,
+) -> Option<&'a mut Episode> {
+    episodes
+        .filter(|episode| belongs_to(path, &episode.path))
+        .max_by_key(|episode| episode.path.as_os_str().len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episodes(names: &[&str]) -> Vec<Episode> {
+        names.iter().map(|name| Episode::new(*name)).collect()
+    }
+
+    #[test]
+    fn numbered_episode_is_found_by_number() {
+        let episodes = episodes(&["Show - 01.mkv", "Show - 02.mkv", "Show - 04.mkv"]);
+        assert_eq!(find_episode(&episodes, 4), Some(&episodes[2]));
+        assert_eq!(first_episode_number(&episodes), 1);
+        assert_eq!(last_episode_number(&episodes), Some(4));
+    }
+
+    #[test]
+    fn absolutely_numbered_chapter_starts_after_1() {
+        let episodes = episodes(&["Show - 13.mkv", "Show - 14.mkv", "Show - 15.mkv"]);
+        let first = first_episode_number(&episodes);
+        assert_eq!(first, 13);
+        assert_eq!(find_episode(&episodes, first), Some(&episodes[0]));
+        assert_eq!(find_episode(&episodes, 1), None);
+    }
+
+    #[test]
+    fn missing_numbered_episode_is_not_found() {
+        let episodes = episodes(&["Show - 01.mkv", "Show - 02.mkv", "Show - 04.mkv"]);
+        assert_eq!(find_episode(&episodes, 3), None);
+        assert_eq!(find_episode(&episodes, 5), None);
+    }
+
+    #[test]
+    fn unnumbered_episodes_are_found_by_position() {
+        let episodes = episodes(&["Intro.mkv", "Middle.mkv", "Outro.mkv"]);
+        assert_eq!(find_episode(&episodes, 2), Some(&episodes[1]));
+        assert_eq!(first_episode_number(&episodes), 1);
+        assert_eq!(last_episode_number(&episodes), Some(3));
+    }
+
+    #[test]
+    fn several_seasons_are_found_by_position() {
+        let episodes = episodes(&["S01E01.mkv", "S01E02.mkv", "S02E01.mkv"]);
+        assert!(!is_numbered(&episodes));
+        assert_eq!(find_episode(&episodes, 3), Some(&episodes[2]));
+        assert_eq!(last_episode_number(&episodes), Some(3));
+    }
+}
@@ -0,0 +1,85 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Episode;
+use crate::episode::is_numbered;
+
+/// File of the current episode remembered alongside its number, so adding
+/// or removing files in the chapter directory doesn't move the progress
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpisodeAnchor {
+    pub(crate) file_name: String,
+    pub(crate) size: Option<u64>,
+    /// Start of the chapter embedded into the file
+    #[serde(default)]
+    pub(crate) start: Option<Duration>,
+}
+
+impl EpisodeAnchor {
+    /// Remember the episode's file
+    #[must_use]
+    pub fn new(episode: &Episode) -> Self {
+        Self {
+            file_name: episode.file_name().into_owned(),
+            size: episode.size(),
+            start: episode.start(),
+        }
+    }
+
+    /// Find the remembered file in the episode list and return its number.
+    /// If the file was renamed it is looked up by its size.
+    #[must_use]
+    pub fn find(&self, episodes: &[Episode]) -> Option<u8> {
+        let position = episodes
+            .iter()
+            .position(|episode| {
+                episode.file_name() == self.file_name && episode.start() == self.start
+            })
+            .or_else(|| {
+                let size = self.size?;
+                let mut same_size = episodes.iter().enumerate().filter(|(_, episode)| {
+                    episode.size() == Some(size) && episode.start() == self.start
+                });
+                let (position, _) = same_size.next()?;
+                same_size.next().is_none().then_some(position)
+            })?;
+        episode_number(episodes, position)
+    }
+}
+
+/// Result of matching the remembered episode file with the files on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reconciliation {
+    /// The file has the remembered number
+    Unchanged,
+    /// The file has moved and its number was corrected
+    Moved {
+        /// Remembered number
+        from: u8,
+        /// Number of the file now
+        to: u8,
+    },
+    /// The file is not in the chapter anymore
+    Missing {
+        /// Name of the remembered file
+        file_name: String,
+    },
+}
+
+/// Number by which the episode on `position` is found by
+/// [`find_episode`](crate::episode::find_episode)
+pub fn episode_number(episodes: &[Episode], position: usize) -> Option<u8> {
+    if is_numbered(episodes) {
+        let number = episodes[position].number()?;
+        u8::try_from(number.episode).ok()
+    } else {
+        u8::try_from(position + 1).ok()
+    }
+}
@@ -0,0 +1,126 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Season and episode numbers recognized in a file name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EpisodeNumber {
+    /// Season, if the name has one
+    pub season: Option<u16>,
+    /// Episode in the season or absolute number
+    pub episode: u16,
+}
+
+impl EpisodeNumber {
+    const fn new(season: Option<u16>, episode: u16) -> Self {
+        Self { season, episode }
+    }
+
+    /// Try to find season and episode numbers in the file name.
+    ///
+    /// Recognized forms are `S01E05`, `1x05`, `Ep 05`, `Episode 5`, `Chapter 5`,
+    /// `E05` at the start of the name, `- 05 -` (including absolute anime
+    /// numbering like `- 105`), `[Group] Title 05` and leading track numbers
+    /// like `05 - Title` or `05. Title`.
+    pub fn parse(file_name: &str) -> Option<Self> {
+        static SEASON_EPISODE: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"(?i)(?:^|[^a-z])s(\d{1,3})[ ._-]?e(\d{1,4})(?:[^0-9]|$)").unwrap()
+        });
+        static CROSS: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"(?i)(?:^|[^0-9a-z])(\d{1,2})x(\d{1,4})(?:[^0-9]|$)").unwrap()
+        });
+        static EPISODE: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"(?i)(?:^|[^a-z])(?:episode|ep|chapter|track)[ ._]*(\d{1,4})(?:[^0-9]|$)")
+                .unwrap()
+        });
+        static LEADING_E: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)^e(\d{1,4})(?:[^0-9a-z]|$)").unwrap());
+        static DASHED: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"\s-\s(\d{1,4})(?:v\d)?(?:\s|$|\[|\()").unwrap());
+        static BRACKETED: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"^\[[^\]]*\][^\[\(]*?\s(\d{1,4})(?:v\d)?\s*(?:\[|\(|$)").unwrap()
+        });
+        static TRACK: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"^(\d{1,3})(?:[ ._-]|$)").unwrap());
+
+        if let Some(captures) = SEASON_EPISODE
+            .captures(file_name)
+            .or_else(|| CROSS.captures(file_name))
+        {
+            let season = captures[1].parse().ok()?;
+            let episode = captures[2].parse().ok()?;
+            return Some(Self::new(Some(season), episode));
+        }
+        [&EPISODE, &LEADING_E, &DASHED, &BRACKETED, &TRACK]
+            .into_iter()
+            .find_map(|regex| regex.captures(file_name))
+            .and_then(|captures| captures[1].parse().ok())
+            .map(|episode| Self::new(None, episode))
+    }
+}
+
+impl fmt::Display for EpisodeNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(season) = self.season {
+            write!(f, "S{season:02}")?;
+        }
+        write!(f, "E{:02}", self.episode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(file_name: &str) -> Option<(Option<u16>, u16)> {
+        EpisodeNumber::parse(file_name).map(|number| (number.season, number.episode))
+    }
+
+    #[test]
+    fn season_and_episode() {
+        assert_eq!(parse("Show.S01E02.1080p"), Some((Some(1), 2)));
+        assert_eq!(parse("show s02 e10"), Some((Some(2), 10)));
+        assert_eq!(parse("Show 1x02 Title"), Some((Some(1), 2)));
+    }
+
+    #[test]
+    fn episode_words() {
+        assert_eq!(parse("Show Episode 5"), Some((None, 5)));
+        assert_eq!(parse("Show.Ep.07.Title"), Some((None, 7)));
+        assert_eq!(parse("E03 - Title"), Some((None, 3)));
+        assert_eq!(parse("Chapter 12"), Some((None, 12)));
+    }
+
+    #[test]
+    fn dashed_and_anime_numbering() {
+        assert_eq!(parse("Show - 02 - Title"), Some((None, 2)));
+        assert_eq!(parse("[Group] Show - 105 [1080p]"), Some((None, 105)));
+        assert_eq!(parse("[Group] Show - 07v2 (BD)"), Some((None, 7)));
+        assert_eq!(parse("[Group] Show 24 [720p]"), Some((None, 24)));
+    }
+
+    #[test]
+    fn leading_track_number() {
+        assert_eq!(parse("05 - Title"), Some((None, 5)));
+        assert_eq!(parse("05. Title"), Some((None, 5)));
+    }
+
+    #[test]
+    fn bare_e_is_not_an_episode() {
+        assert_eq!(parse("Movie.x264-e2"), None);
+        assert_eq!(parse("Title e 2"), None);
+    }
+
+    #[test]
+    fn missing_number() {
+        assert_eq!(parse("Trailer"), None);
+        assert_eq!(parse("Movie (2019)"), None);
+    }
+}
@@ -0,0 +1,256 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::config::UserDataDirNotFoundError;
+use crate::utils::OpenError;
+
+/// Everything that can go wrong while reading, changing or watching media
+#[derive(Debug, Clone, thiserror::Error)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Media can't be written as JSON
+    #[error("{name}: Serialize error: {source}")]
+    Serialize {
+        /// Name of the media
+        name: String,
+        /// Error of the serializer
+        source: Arc<serde_json::Error>,
+    },
+    /// File of the media or the settings is not valid JSON
+    #[error("{path}: file parsing error: {source}")]
+    Deserialize {
+        /// The file
+        path: PathBuf,
+        /// Error of the parser
+        source: Arc<serde_json::Error>,
+    },
+    /// No chapter after the current one
+    #[error("{path}: Failed to find next chapter path")]
+    FindNextChapterPath {
+        /// Current chapter
+        path: PathBuf,
+    },
+    /// No chapter before the current one
+    #[error("{path}: Failed to find previous chapter path")]
+    FindPrevChapterPath {
+        /// Current chapter
+        path: PathBuf,
+    },
+    /// Another media has the name
+    #[error("Name \"{name}\" is used")]
+    MediaNameIsUsed {
+        /// The name
+        name: String,
+    },
+    /// No episode with the number in the chapter
+    #[error("Eisode not found")]
+    EpisodeNotFound,
+    /// The data directory can't be used
+    #[error("Failed to determinate data directory: {path}")]
+    DataDir {
+        /// The directory
+        path: PathBuf,
+    },
+    /// The default program of the system can't be started
+    #[error(transparent)]
+    Open(#[from] OpenError),
+    /// Reading or writing a file failed
+    #[error(transparent)]
+    Io(#[from] Arc<io::Error>),
+    /// Directories of the user are unknown
+    #[error(transparent)]
+    UserDataDirNotFound(#[from] UserDataDirNotFoundError),
+    /// The path has no parent directory
+    #[error("{path}: Falied to find parent directory")]
+    FindParent {
+        /// The path
+        path: PathBuf,
+    },
+    /// Chapter pattern can't be compiled
+    #[error("Invalid pattern \"{pattern}\": {message}")]
+    InvalidPattern {
+        /// The pattern
+        pattern: String,
+        /// What is wrong with it
+        message: String,
+    },
+    /// Playlist can't be read
+    #[error("{path}: Failed to parse playlist: {message}")]
+    ParsePlaylist {
+        /// The playlist
+        path: PathBuf,
+        /// What is wrong with it
+        message: String,
+    },
+    /// The list of recently used files can't be read
+    #[error("{path}: Failed to parse recently used files: {message}")]
+    ParseRecentlyUsed {
+        /// The list file
+        path: PathBuf,
+        /// What is wrong with it
+        message: String,
+    },
+    /// ZIP archive can't be read
+    #[error("{path}: Failed to read archive: {source}")]
+    Archive {
+        /// The archive
+        path: PathBuf,
+        /// Error of the ZIP reader
+        source: Arc<zip::result::ZipError>,
+    },
+    /// Archive entry is bigger than the extraction limit
+    #[error("{name}: File is too big to extract ({size} bytes, limit is {limit} bytes)")]
+    ArchiveEntryTooBig {
+        /// Name of the entry
+        name: String,
+        /// Size of the entry in bytes
+        size: u64,
+        /// The limit in bytes
+        limit: u64,
+    },
+    /// Player command can't be split into arguments
+    #[error("Player command has unbalanced quotes: {command}")]
+    PlayerCommand {
+        /// The command
+        command: String,
+    },
+    /// Player can't be started
+    #[error("Failed to run player \"{program}\": {source}")]
+    RunPlayer {
+        /// Program of the player command
+        program: String,
+        /// Error of the start
+        source: Arc<io::Error>,
+    },
+    /// Kodi video database can't be read
+    #[error("{path}: Failed to read Kodi database: {message}")]
+    KodiDatabase {
+        /// The database file
+        path: PathBuf,
+        /// What went wrong
+        message: String,
+    },
+    /// Cover can't be turned into a thumbnail
+    #[error("{path}: Failed to make thumbnail: {message}")]
+    Thumbnail {
+        /// The cover
+        path: PathBuf,
+        /// What went wrong
+        message: String,
+    },
+    /// Path starting with `~` can't be expanded
+    #[error("Failed to expand tilde {0}")]
+    PathTildeExpand(Arc<expand_tilde::Error>),
+}
+
+impl ErrorKind {
+    pub(crate) fn serialize(source: serde_json::Error, name: impl Into<String>) -> Self {
+        let name = name.into();
+        let source = source.into();
+        Self::Serialize { name, source }
+    }
+
+    pub(crate) fn deserialize(path: impl Into<PathBuf>, source: serde_json::Error) -> Self {
+        let path = path.into();
+        let source = source.into();
+        Self::Deserialize { path, source }
+    }
+
+    pub(crate) fn media_name_is_used(name: impl Into<String>) -> Self {
+        Self::MediaNameIsUsed { name: name.into() }
+    }
+
+    pub(crate) fn data_dir(path: impl Into<PathBuf>) -> Self {
+        Self::DataDir { path: path.into() }
+    }
+
+    pub(crate) fn find_next_chapter(path: impl Into<PathBuf>) -> Self {
+        Self::FindNextChapterPath { path: path.into() }
+    }
+
+    pub(crate) fn find_prev_chapter(path: impl Into<PathBuf>) -> Self {
+        Self::FindPrevChapterPath { path: path.into() }
+    }
+
+    pub(crate) fn invalid_pattern(pattern: impl Into<String>, message: impl Into<String>) -> Self {
+        let pattern = pattern.into();
+        let message = message.into();
+        Self::InvalidPattern { pattern, message }
+    }
+
+    pub(crate) fn parse_playlist(path: impl Into<PathBuf>, message: impl Into<String>) -> Self {
+        let path = path.into();
+        let message = message.into();
+        Self::ParsePlaylist { path, message }
+    }
+
+    pub(crate) fn parse_recently_used(
+        path: impl Into<PathBuf>,
+        message: impl Into<String>,
+    ) -> Self {
+        let path = path.into();
+        let message = message.into();
+        Self::ParseRecentlyUsed { path, message }
+    }
+
+    pub(crate) fn archive(path: impl Into<PathBuf>, source: zip::result::ZipError) -> Self {
+        let path = path.into();
+        let source = source.into();
+        Self::Archive { path, source }
+    }
+
+    pub(crate) fn archive_entry_too_big(name: impl Into<String>, size: u64, limit: u64) -> Self {
+        let name = name.into();
+        Self::ArchiveEntryTooBig { name, size, limit }
+    }
+
+    pub(crate) fn player_command(command: impl Into<String>) -> Self {
+        Self::PlayerCommand {
+            command: command.into(),
+        }
+    }
+
+    pub(crate) fn run_player(program: impl Into<String>, source: io::Error) -> Self {
+        let program = program.into();
+        let source = source.into();
+        Self::RunPlayer { program, source }
+    }
+
+    pub(crate) fn kodi_database(path: impl Into<PathBuf>, message: impl Into<String>) -> Self {
+        let path = path.into();
+        let message = message.into();
+        Self::KodiDatabase { path, message }
+    }
+
+    pub(crate) fn thumbnail(path: impl Into<PathBuf>, message: impl Into<String>) -> Self {
+        let path = path.into();
+        let message = message.into();
+        Self::Thumbnail { path, message }
+    }
+
+    pub(crate) fn find_parent(path: impl Into<PathBuf>) -> Self {
+        Self::FindParent { path: path.into() }
+    }
+}
+
+impl From<io::Error> for ErrorKind {
+    fn from(value: io::Error) -> Self {
+        Self::Io(Arc::new(value))
+    }
+}
+
+impl From<expand_tilde::Error> for ErrorKind {
+    fn from(value: expand_tilde::Error) -> Self {
+        Self::PathTildeExpand(value.into())
+    }
+}
+
+/// Result with [`ErrorKind`]
+pub type Result<T> = std::result::Result<T, ErrorKind>;
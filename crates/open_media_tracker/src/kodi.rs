@@ -0,0 +1,305 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Watched state from the video database of Kodi
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use etcetera::BaseStrategy;
+use expand_tilde::ExpandTilde;
+use rusqlite::{Connection, OpenFlags};
+
+use crate::{Config, ErrorKind, MediaHandler, MediaList, Result, Settings, chapter_number};
+
+const DATABASE_PREFIX: &str = "MyVideos";
+
+/// Last played episode of each show, resume points are bookmarks of type 1
+const LAST_PLAYED_QUERY: &str = "
+    SELECT episode.idShow, tvshow.c00, path.strPath, files.strFilename, episode.c12,
+        files.playCount, files.lastPlayed, bookmark.timeInSeconds,
+        (SELECT show_path.strPath FROM tvshowlinkpath
+            JOIN path AS show_path ON show_path.idPath = tvshowlinkpath.idPath
+            WHERE tvshowlinkpath.idShow = episode.idShow LIMIT 1)
+    FROM episode
+    JOIN files ON files.idFile = episode.idFile
+    JOIN path ON path.idPath = files.idPath
+    JOIN tvshow ON tvshow.idShow = episode.idShow
+    LEFT JOIN bookmark ON bookmark.idFile = files.idFile AND bookmark.type = 1
+    WHERE files.lastPlayed IS NOT NULL AND files.lastPlayed != ''
+    ORDER BY files.lastPlayed DESC";
+
+/// Show along with its last played episode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KodiShow {
+    /// Title of the show in the Kodi library
+    pub title: String,
+    /// Directory of the show
+    pub series_path: Option<PathBuf>,
+    /// File of the last played episode
+    pub episode_path: PathBuf,
+    /// Season of the last played episode
+    pub season: Option<u8>,
+    /// The episode was played to the end
+    pub watched: bool,
+    /// Where playback of the episode stopped
+    pub position: Option<Duration>,
+    /// When the episode was played
+    pub last_played: DateTime<Local>,
+}
+
+/// The latest video database of Kodi
+#[must_use]
+pub fn kodi_database() -> Option<PathBuf> {
+    let home_dir = etcetera::home_dir().ok()?;
+    let native_dir = etcetera::base_strategy::choose_native_strategy()
+        .ok()
+        .map(|dirs| dirs.data_dir().join("Kodi"));
+    [
+        Some(home_dir.join(".kodi")),
+        Some(home_dir.join(".var/app/tv.kodi.Kodi/data")),
+        native_dir,
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|dir| latest_database(&dir.join("userdata").join("Database")))
+}
+
+/// Databases are named by the schema version as `MyVideos131.db`
+fn latest_database(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(std::result::Result::ok)
+        .filter_map(|entry| {
+            let file_name = entry.file_name();
+            let version: u32 = file_name
+                .to_str()?
+                .strip_prefix(DATABASE_PREFIX)?
+                .strip_suffix(".db")?
+                .parse()
+                .ok()?;
+            Some((version, entry.path()))
+        })
+        .max_by_key(|(version, _)| *version)
+        .map(|(_, path)| path)
+}
+
+/// Read the last played episode of every show stored on the local file
+/// system
+///
+/// # Errors
+///
+/// Fails if the database can't be opened or has unknown schema.
+pub async fn read_kodi_shows(path: impl Into<PathBuf>) -> Result<Vec<KodiShow>> {
+    let path = path.into();
+    tokio::task::spawn_blocking(move || {
+        read_shows(&path).map_err(|err| ErrorKind::kodi_database(path, err.to_string()))
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+fn read_shows(path: &Path) -> rusqlite::Result<Vec<KodiShow>> {
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut statement = connection.prepare(LAST_PLAYED_QUERY)?;
+    let mut rows = statement.query([])?;
+    let mut seen = HashSet::new();
+    let mut shows = Vec::new();
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        if !seen.insert(id) {
+            continue;
+        }
+        let dir: String = row.get(2)?;
+        let file_name: String = row.get(3)?;
+        // Network shares and stacks of files are only known to Kodi
+        if !is_local(&dir) || !is_local(&file_name) {
+            continue;
+        }
+        let last_played: String = row.get(6)?;
+        let Some(last_played) = parse_date(&last_played) else {
+            continue;
+        };
+        let season: Option<String> = row.get(4)?;
+        let play_count: Option<i64> = row.get(5)?;
+        let position: Option<f64> = row.get(7)?;
+        let series_path: Option<String> = row.get(8)?;
+        shows.push(KodiShow {
+            title: row.get(1)?,
+            // Kodi keeps the trailing slash of directories
+            series_path: series_path
+                .filter(|path| is_local(path))
+                .map(|path| Path::new(&path).components().collect()),
+            episode_path: Path::new(&dir).join(file_name),
+            season: season.and_then(|season| season.parse().ok()),
+            watched: play_count.is_some_and(|count| count > 0),
+            position: position.and_then(|secs| Duration::try_from_secs_f64(secs).ok()),
+            last_played,
+        });
+    }
+    Ok(shows)
+}
+
+fn is_local(path: &str) -> bool {
+    !path.contains("://")
+}
+
+/// The chapter path of the media may start with `~`
+fn is_chapter(media: &MediaHandler, path: &Path) -> bool {
+    media
+        .chapter_path()
+        .expand_tilde()
+        .is_ok_and(|chapter_path| chapter_path == path)
+}
+
+/// Dates are stored in local time as `2024-05-17 21:03:11`
+fn parse_date(date: &str) -> Option<DateTime<Local>> {
+    let date = NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").ok()?;
+    Local.from_local_datetime(&date).earliest()
+}
+
+/// Import the last played episode of every show in the database, shows
+/// which fail to import are only logged. Returns indexes of changed media
+///
+/// Created media is detached if `detached` is set, so a detached copy of
+/// the list writes nothing.
+///
+/// # Errors
+///
+/// Fails if the database can't be read.
+pub async fn import_kodi(
+    media_list: &mut MediaList,
+    path: impl Into<PathBuf>,
+    settings: &Settings,
+    config: Arc<Config>,
+    detached: bool,
+) -> Result<Vec<usize>> {
+    let mut changed = Vec::new();
+    for show in read_kodi_shows(path).await? {
+        match import_show(media_list, &show, settings, config.clone(), detached).await {
+            Ok(Some(id)) => changed.push(id),
+            Ok(None) => {}
+            Err(err) => log::warn!("{}: {err}", show.title),
+        }
+    }
+    Ok(changed)
+}
+
+/// Move the media to the last played episode of the show.
+///
+/// Media is found by the name of the show or by the season directory and is
+/// created if there is no such one. The next episode is taken if the last
+/// one was played to the end. Returns the index of the media, `None` if the
+/// media was changed after the episode was played
+async fn import_show(
+    media_list: &mut MediaList,
+    show: &KodiShow,
+    settings: &Settings,
+    config: Arc<Config>,
+    detached: bool,
+) -> Result<Option<usize>> {
+    let Some(chapter_path) = show.episode_path.parent() else {
+        return Ok(None);
+    };
+    let found = media_list.position(&show.title).or_else(|| {
+        media_list
+            .iter()
+            .position(|media| is_chapter(media, chapter_path))
+    });
+    let id = match found {
+        Some(id) if media_list[id].changing_date() >= show.last_played => return Ok(None),
+        Some(id) => id,
+        None if detached => media_list.insert(MediaHandler::new_detached(&show.title, config)),
+        None => media_list.add(&show.title, config)?,
+    };
+    let media = &mut media_list[id];
+    let new_chapter = !is_chapter(media, chapter_path);
+    if new_chapter {
+        media.set_chapter_path(chapter_path)?;
+    }
+    let series_path = show
+        .series_path
+        .as_deref()
+        .filter(|path| chapter_path.starts_with(path) && chapter_path != *path)
+        .filter(|_| media.series_path().is_none());
+    if let Some(series_path) = series_path {
+        media.set_series_path(Some(series_path.to_path_buf()))?;
+    }
+    if new_chapter || series_path.is_some() {
+        media.reload_metadata().await?;
+    }
+    // Kodi numbers seasons by their names, which may skip some directories
+    let chapters = media.chapter_list(settings).await?;
+    if let Some(chapter) = chapter_number(&chapters, chapter_path) {
+        media.set_chapter(chapter)?;
+    }
+    let episodes = media.episode_list(settings).await?;
+    let (mut number, _) = episodes
+        .find_playing(&show.episode_path, Duration::ZERO)
+        .ok_or(ErrorKind::EpisodeNotFound)?;
+    let finished = show.watched && show.position.is_none();
+    if finished
+        && episodes
+            .last_number()
+            .is_some_and(|last| usize::from(number) < last)
+    {
+        number += 1;
+    }
+    if number != media.episode() {
+        media.set_episode(number)?;
+    }
+    if let Some(episode) = episodes.find(number) {
+        media.set_episode_anchor(episode)?;
+    }
+    media.set_position(show.position)?;
+    Ok(Some(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use fs_err as fs;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn detached_import_resolves_chapter_by_path() {
+        let dir = std::env::temp_dir().join(format!("omt-kodi-{}", std::process::id()));
+        let series_path = dir.join("Show");
+        for season in ["Season 2", "Season 3"] {
+            fs::create_dir_all(series_path.join(season)).unwrap();
+            fs::write(series_path.join(season).join("01.mkv"), []).unwrap();
+        }
+        let config = Arc::new(Config {
+            data_dir: dir.join("data"),
+            config_dir: dir.clone(),
+            archive_cache: dir.clone(),
+            thumbnails: dir.clone(),
+        });
+        let show = KodiShow {
+            title: "Show".to_string(),
+            series_path: Some(series_path.clone()),
+            episode_path: series_path.join("Season 3/01.mkv"),
+            season: Some(3),
+            watched: false,
+            position: Some(Duration::from_secs(10)),
+            last_played: Local::now(),
+        };
+        let mut media_list = MediaList::new();
+        let id = import_show(&mut media_list, &show, &Settings::default(), config, true)
+            .await
+            .unwrap()
+            .unwrap();
+        let media = &media_list[id];
+        // Season 3 of Kodi is the second directory of the series
+        assert_eq!((media.chapter(), media.episode()), (2, 1));
+        assert!(!dir.join("data").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
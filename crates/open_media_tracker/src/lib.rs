@@ -0,0 +1,72 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Tracking of watched episodes of series and audiobooks.
+//!
+//! Media is stored as a JSON file in the data directory of [`Config`]. Each
+//! one remembers the current chapter, which is a directory, playlist or
+//! archive with episodes, and the current episode in it. [`MediaList::read`]
+//! reads all media, [`MediaHandler`] changes one and saves it on every
+//! change. Episodes of the chapter are found by [`read_episodes`], the next
+//! chapter is found by [`MediaHandler::next_chapter_path`] among siblings of
+//! the current one or in the series directory.
+
+#![warn(missing_docs)]
+
+pub mod utils;
+
+mod archive;
+mod chapter;
+mod chapter_filter;
+mod config;
+mod cover;
+mod cue_sheet;
+mod episode;
+mod episode_anchor;
+mod episode_number;
+mod error;
+mod kodi;
+mod media;
+mod media_detector;
+mod media_handler;
+mod media_list;
+mod nfo;
+mod player;
+mod playlist;
+mod probe;
+mod recently_used;
+mod remote_api;
+mod scan_options;
+mod settings;
+mod sidecar;
+mod sort_order;
+mod watch_later;
+
+pub use archive::{ArchiveLimits, extract, is_archive};
+pub use chapter::{Chapter, chapter_number};
+pub use chapter_filter::{ChapterFilter, ChapterMatcher};
+pub use config::Config;
+pub use cover::{remove_unused_thumbnails, thumbnail};
+pub use episode::{Episode, EpisodeList, read_episodes};
+pub use episode_anchor::{EpisodeAnchor, Reconciliation};
+pub use episode_number::EpisodeNumber;
+pub use error::{ErrorKind, Result};
+pub use kodi::{KodiShow, import_kodi, kodi_database, read_kodi_shows};
+pub use media_detector::MediaExtensions;
+pub use media_handler::MediaHandler;
+pub use media_list::{MediaList, MediaListRef, MediaListRefMut};
+pub use nfo::Metadata;
+pub use player::{PlayWarning, Player};
+pub use playlist::is_playlist;
+pub use recently_used::{
+    RecentEpisode, RecentFile, find_recent_episode, read_recently_used, recently_used_file,
+};
+pub use remote_api::RemoteApi;
+pub use scan_options::ScanOptions;
+pub use settings::Settings;
+pub use sidecar::{Sidecar, SidecarKind};
+pub use sort_order::SortOrder;
+pub use watch_later::{ResumePoint, find_resume_point, watch_later_dir};
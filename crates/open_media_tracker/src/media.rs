@@ -0,0 +1,174 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::DateTime;
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+
+use crate::utils;
+use crate::{
+    ChapterFilter, ChapterMatcher, EpisodeAnchor, EpisodeList, ErrorKind, Metadata, Reconciliation,
+    Result, ScanOptions, Settings, SortOrder,
+};
+
+use super::chapter::read_chapters;
+use super::episode::read_episodes;
+use super::{Chapter, Episode};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Media {
+    pub(crate) name: String,
+    pub(crate) chapter: u8,
+    pub(crate) episode: u8,
+    pub(crate) chapter_path: PathBuf,
+    pub(crate) adding_date: DateTime<chrono::Local>,
+    pub(crate) changing_date: DateTime<chrono::Local>,
+    /// Media saved before natural sorting keep the order their episode
+    /// numbers were counted in
+    #[serde(default = "legacy_sort_order")]
+    pub(crate) sort_order: SortOrder,
+    #[serde(default)]
+    pub(crate) episode_anchor: Option<EpisodeAnchor>,
+    /// Directory with chapters as subdirectories
+    #[serde(default)]
+    pub(crate) series_path: Option<PathBuf>,
+    /// Patterns for chapter directories in addition to the global ones
+    #[serde(default)]
+    pub(crate) chapter_filter: ChapterFilter,
+    /// Where playback of the current episode stopped
+    #[serde(default)]
+    pub(crate) position: Option<Duration>,
+    /// Details of the show found next to the chapter
+    #[serde(default)]
+    pub(crate) metadata: Metadata,
+    /// Image chosen instead of the found cover
+    #[serde(default)]
+    pub(crate) cover: Option<PathBuf>,
+}
+
+const fn legacy_sort_order() -> SortOrder {
+    SortOrder::Lexical
+}
+
+impl Media {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            chapter: 1,
+            episode: 1,
+            chapter_path: PathBuf::new(),
+            adding_date: chrono::Local::now(),
+            changing_date: chrono::Local::now(),
+            sort_order: SortOrder::default(),
+            episode_anchor: None,
+            series_path: None,
+            chapter_filter: ChapterFilter::default(),
+            position: None,
+            metadata: Metadata::default(),
+            cover: None,
+        }
+    }
+
+    pub async fn read(path: &Path) -> Result<Self> {
+        let file_content = fs_err::tokio::read_to_string(&path).await?;
+        let media = serde_json::from_str(&file_content)
+            .map_err(|source| ErrorKind::deserialize(path, source))?;
+        Ok(media)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+
+        let parent = path.parent().ok_or_else(|| ErrorKind::data_dir(path))?;
+        if !parent.exists() {
+            fs::create_dir(path)?;
+        }
+        let mut file = fs::File::create(path)?;
+        serde_json::to_writer_pretty(&file, &self)
+            .map_err(|source| ErrorKind::serialize(source, &self.name))?;
+        file.write_all(b"\n")?;
+        Ok(())
+    }
+
+    pub fn next_chapter_path<'a>(
+        &self,
+        settings: &Settings,
+    ) -> impl Future<Output = Result<PathBuf>> + 'a {
+        let path = self.chapter_path.clone();
+        let options = self.scan_options(settings);
+        let matcher = self.chapter_matcher(settings);
+        async move { utils::next_dir(path, &options, &matcher?).await }
+    }
+
+    pub fn prev_chapter_path<'a>(
+        &self,
+        settings: &Settings,
+    ) -> impl Future<Output = Result<PathBuf>> + 'a {
+        let path = self.chapter_path.clone();
+        let options = self.scan_options(settings);
+        let matcher = self.chapter_matcher(settings);
+        async move { utils::prev_dir(path, &options, &matcher?).await }
+    }
+
+    fn chapter_matcher(&self, settings: &Settings) -> Result<ChapterMatcher> {
+        ChapterMatcher::new([&settings.chapter_filter, &self.chapter_filter])
+    }
+
+    fn scan_options(&self, settings: &Settings) -> ScanOptions {
+        ScanOptions {
+            sort_order: self.sort_order,
+            media_extensions: settings.media_extensions.clone(),
+            series_path: self.series_path.clone(),
+        }
+    }
+
+    /// Match the remembered episode file with the episode list and correct
+    /// the episode number if the file has moved
+    pub fn reconcile_episode(&mut self, episodes: &[Episode]) -> Reconciliation {
+        let Some(anchor) = &self.episode_anchor else {
+            return Reconciliation::Unchanged;
+        };
+        match anchor.find(episodes) {
+            Some(number) if number == self.episode => Reconciliation::Unchanged,
+            Some(number) => {
+                let from = self.episode;
+                self.episode = number;
+                Reconciliation::Moved { from, to: number }
+            }
+            None => Reconciliation::Missing {
+                file_name: anchor.file_name.clone(),
+            },
+        }
+    }
+
+    pub fn episode_list<'a>(
+        &self,
+        settings: &Settings,
+    ) -> impl Future<Output = Result<EpisodeList>> + 'a {
+        let path = self.chapter_path.clone();
+        let options = self.scan_options(settings);
+        async move { read_episodes(path, &options).await }
+    }
+
+    pub fn chapter_list<'a>(
+        &self,
+        settings: &Settings,
+    ) -> impl Future<Output = Result<Vec<Chapter>>> + 'a {
+        let series_path = self.series_path.clone();
+        let options = self.scan_options(settings);
+        let matcher = self.chapter_matcher(settings);
+        async move {
+            match series_path {
+                Some(series_path) => read_chapters(series_path, &options, &matcher?).await,
+                None => Ok(Vec::new()),
+            }
+        }
+    }
+}
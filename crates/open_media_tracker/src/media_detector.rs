@@ -0,0 +1,163 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use derive_more::Display;
+use infer::MatcherType;
+use mime_guess::mime;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncReadExt;
+
+/// How many bytes from the beginning of the file are used for detection
+const SNIFF_LEN: usize = 8192;
+/// Size of the MPEG transport stream packet
+const TS_PACKET_LEN: usize = 188;
+const TS_SYNC_BYTE: u8 = 0x47;
+
+/// Extensions which are always (allow) or never (deny) treated as media
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaExtensions {
+    /// Extensions of media files which are not recognized by default
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Extensions of files which are never media
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl MediaExtensions {
+    fn contains(list: &[String], extension: &str) -> bool {
+        list.iter()
+            .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(extension))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum SkipReason {
+    #[display("extension is in the deny list")]
+    DeniedExtension,
+    #[display("not a media file ({mime})")]
+    NotMediaType { mime: String },
+    #[display("content is not recognized as media")]
+    UnknownContent,
+    #[display("failed to read: {message}")]
+    Unreadable { message: String },
+    #[display("no episode with the same name")]
+    NoEpisode,
+    #[display("file not found")]
+    NotFound,
+}
+
+/// Decide whether the file is a video or audio file. The extension lists
+/// are checked first, then MIME type guessed by extension. Files whose
+/// extension is unknown or not of a media type (like `.bin` or `.dat`) are
+/// detected by their content.
+pub async fn detect_media(path: &Path, extensions: &MediaExtensions) -> Result<(), SkipReason> {
+    match detect_media_by_extension(path, extensions) {
+        Some(Err(SkipReason::NotMediaType { mime })) => match sniff(path).await {
+            Err(SkipReason::UnknownContent) => Err(SkipReason::NotMediaType { mime }),
+            res => res,
+        },
+        Some(res) => res,
+        None => sniff(path).await,
+    }
+}
+
+/// Check the extension lists and MIME type guessed by extension. `None` if
+/// the extension is unknown and the content must be checked
+pub fn detect_media_by_extension(
+    path: &Path,
+    extensions: &MediaExtensions,
+) -> Option<Result<(), SkipReason>> {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy())
+        .unwrap_or_default();
+    if !extension.is_empty() {
+        if MediaExtensions::contains(&extensions.deny, &extension) {
+            return Some(Err(SkipReason::DeniedExtension));
+        }
+        if MediaExtensions::contains(&extensions.allow, &extension) {
+            return Some(Ok(()));
+        }
+    }
+    let mime = mime_guess::from_path(path).first()?;
+    let mtype = mime.type_();
+    if mtype == mime::VIDEO || mtype == mime::AUDIO {
+        return Some(Ok(()));
+    }
+    Some(Err(SkipReason::NotMediaType {
+        mime: mime.to_string(),
+    }))
+}
+
+async fn sniff(path: &Path) -> Result<(), SkipReason> {
+    let unreadable = |err: std::io::Error| SkipReason::Unreadable {
+        message: err.to_string(),
+    };
+    let file = fs_err::tokio::File::open(path).await.map_err(unreadable)?;
+    let mut buf = Vec::with_capacity(SNIFF_LEN);
+    file.take(SNIFF_LEN as u64)
+        .read_to_end(&mut buf)
+        .await
+        .map_err(unreadable)?;
+    let is_media = infer::get(&buf)
+        .is_some_and(|kind| matches!(kind.matcher_type(), MatcherType::Video | MatcherType::Audio));
+    if is_media || is_transport_stream(&buf) {
+        return Ok(());
+    }
+    Err(SkipReason::UnknownContent)
+}
+
+fn is_transport_stream(buf: &[u8]) -> bool {
+    buf.len() > TS_PACKET_LEN * 2
+        && buf
+            .iter()
+            .step_by(TS_PACKET_LEN)
+            .take(3)
+            .all(|&byte| byte == TS_SYNC_BYTE)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    async fn detect(file_name: &str, content: &[u8]) -> Result<(), SkipReason> {
+        let dir = std::env::temp_dir().join(format!("omt-detect-{}", std::process::id()));
+        fs_err::create_dir_all(&dir).unwrap();
+        let path = dir.join(file_name);
+        fs_err::File::create(&path)
+            .unwrap()
+            .write_all(content)
+            .unwrap();
+        let res = detect_media(&path, &MediaExtensions::default()).await;
+        fs_err::remove_file(&path).unwrap();
+        res
+    }
+
+    #[tokio::test]
+    async fn media_with_generic_extension_is_sniffed() {
+        let mkv = [
+            0x1A, 0x45, 0xDF, 0xA3, 0x93, 0x42, 0x82, 0x88, b'm', b'a', b't', b'r', b'o', b's',
+            b'k', b'a',
+        ];
+        assert_eq!(detect("episode.bin", &mkv).await, Ok(()));
+        assert_eq!(detect("episode", &mkv).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn other_content_keeps_its_mime() {
+        assert_eq!(
+            detect("notes.txt", b"plain text").await,
+            Err(SkipReason::NotMediaType {
+                mime: String::from("text/plain")
+            })
+        );
+    }
+}
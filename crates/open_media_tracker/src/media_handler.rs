@@ -0,0 +1,565 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::DateTime;
+use derive_more::derive::{Deref, DerefMut};
+use expand_tilde::ExpandTilde;
+use fs_err as fs;
+
+use super::Config;
+use crate::media::Media;
+use crate::{
+    ChapterFilter, Episode, EpisodeAnchor, Metadata, Reconciliation, Result, ResumePoint, Settings,
+    SortOrder, is_playlist,
+};
+
+const DEFAULT_MEDIA_NAME: &str = "New media";
+
+/// Media along with the configuration needed to save it. Every change is
+/// written to the media file
+#[derive(Debug, Clone, Deref, DerefMut)]
+pub struct MediaHandler {
+    #[deref_mut]
+    #[deref]
+    media: Media,
+    config: Arc<Config>,
+    detached: bool,
+}
+
+impl MediaHandler {
+    /// Create media and save it to the data directory
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn new(media_name: impl Into<String>, config: Arc<Config>) -> Result<Self> {
+        let media = Media::new(media_name);
+        let handler = Self {
+            media,
+            config,
+            detached: false,
+        };
+        handler.save()?;
+        Ok(handler)
+    }
+
+    /// Create media with the first unused default name
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn with_default_name(config: Arc<Config>) -> Result<Self> {
+        let name = find_available_name(&config.data_dir);
+        Self::new(name, config)
+    }
+
+    /// Copy of the media whose changes are kept in memory only. Apply them
+    /// to the original with [`MediaHandler::merge`]
+    #[must_use]
+    pub fn detached(&self) -> Self {
+        Self {
+            detached: true,
+            ..self.clone()
+        }
+    }
+
+    /// Create media which is kept in memory until it's attached
+    #[must_use]
+    pub fn new_detached(media_name: impl Into<String>, config: Arc<Config>) -> Self {
+        Self {
+            media: Media::new(media_name),
+            config,
+            detached: true,
+        }
+    }
+
+    /// Save the detached media and its further changes to the data directory
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn attach(&mut self) -> Result<()> {
+        self.detached = false;
+        self.save()
+    }
+
+    /// Apply the progress made on the detached copy since `base`. Fields
+    /// which the copy left untouched keep the values set meanwhile
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn merge(&mut self, base: &Self, changed: &Self) -> Result<()> {
+        if changed.series_path() != base.series_path() {
+            self.set_series_path(changed.media.series_path.clone())?;
+        }
+        let new_chapter = changed.chapter_path() != base.chapter_path();
+        if new_chapter {
+            self.set_chapter_path(changed.chapter_path())?;
+        }
+        if changed.chapter() != base.chapter() {
+            self.set_chapter(changed.chapter())?;
+        }
+        let new_episode = changed.episode() != base.episode();
+        if new_episode {
+            self.set_episode(changed.episode())?;
+        }
+        // Moving to another episode forgets the position
+        if new_chapter || new_episode || changed.position() != base.position() {
+            self.set_position(changed.position())?;
+        }
+        if changed.media.episode_anchor != base.media.episode_anchor {
+            self.media
+                .episode_anchor
+                .clone_from(&changed.media.episode_anchor);
+            self.save()?;
+        }
+        if changed.metadata() != base.metadata() {
+            self.set_metadata(changed.metadata().clone())?;
+        }
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        if !self.detached {
+            self.media.save(self.path())?;
+        }
+        Ok(())
+    }
+
+    fn changed(&mut self) -> Result<()> {
+        self.changing_date = chrono::Local::now();
+        self.save()
+    }
+
+    /// Read media saved in the file
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or parsed.
+    pub async fn read(path: &Path, config: Arc<Config>) -> Result<Self> {
+        let media = Self {
+            media: Media::read(path).await?,
+            config,
+            detached: false,
+        };
+        Ok(media)
+    }
+
+    /// Rename media along with its file
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be renamed or written.
+    pub fn rename(&mut self, new_name: impl Into<String>) -> Result<()> {
+        let new_name = new_name.into();
+        if self.media.name == new_name {
+            return Ok(());
+        }
+        let new_file_name = file_name(&new_name);
+        let new_path = self.config.path_to_media(&new_file_name);
+        if !self.detached {
+            fs::rename(self.path(), &new_path)?;
+        }
+        self.media.name = new_name;
+        self.changed()?;
+        Ok(())
+    }
+
+    /// Remove the file of the media
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be removed.
+    pub fn remove_file(&self) -> Result<()> {
+        if self.detached {
+            return Ok(());
+        }
+        fs::remove_file(self.path())?;
+        Ok(())
+    }
+
+    /// Unique name of the media, also the name of its file
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.media.name
+    }
+
+    /// Number of the current chapter starting from 1
+    #[must_use]
+    pub const fn chapter(&self) -> u8 {
+        self.media.chapter
+    }
+
+    /// Number of the current episode in the chapter starting from 1
+    #[must_use]
+    pub const fn episode(&self) -> u8 {
+        self.media.episode
+    }
+
+    /// Directory, playlist or archive of the current chapter
+    #[must_use]
+    pub fn chapter_path(&self) -> &Path {
+        &self.media.chapter_path
+    }
+
+    /// Directory with chapters as subdirectories
+    #[must_use]
+    pub fn series_path(&self) -> Option<&Path> {
+        self.media.series_path.as_deref()
+    }
+
+    /// Whether the file may be an episode of the media: it is inside the
+    /// chapter directory or the chapter is a playlist. Media without a
+    /// chapter path has no episodes
+    #[must_use]
+    pub fn may_contain(&self, path: &Path) -> bool {
+        let chapter_path = self.chapter_path();
+        if chapter_path.as_os_str().is_empty() {
+            return false;
+        }
+        let Ok(chapter_path) = chapter_path.expand_tilde() else {
+            return false;
+        };
+        path.starts_with(&chapter_path) || is_playlist(&chapter_path)
+    }
+
+    /// Order of episodes and chapters
+    #[must_use]
+    pub const fn sort_order(&self) -> SortOrder {
+        self.media.sort_order
+    }
+
+    /// When the media was changed last time
+    #[must_use]
+    pub const fn changing_date(&self) -> DateTime<chrono::Local> {
+        self.media.changing_date
+    }
+
+    /// Details of the show from NFO files and the folder artwork
+    #[must_use]
+    pub const fn metadata(&self) -> &Metadata {
+        &self.media.metadata
+    }
+
+    /// Read details of the show for the current paths in a blocking task.
+    /// Apply them with [`MediaHandler::set_metadata`]
+    pub fn load_metadata<'a>(&self) -> impl Future<Output = Metadata> + 'a {
+        let chapter_path = self.media.chapter_path.clone();
+        let series_path = self.media.series_path.clone();
+        async move {
+            tokio::task::spawn_blocking(move || {
+                Metadata::read(&chapter_path, series_path.as_deref())
+            })
+            .await
+            .unwrap_or_default()
+        }
+    }
+
+    /// Image chosen instead of the found cover
+    #[must_use]
+    pub fn cover(&self) -> Option<&Path> {
+        self.media.cover.as_deref()
+    }
+
+    /// Where playback of the current episode stopped
+    #[must_use]
+    pub const fn position(&self) -> Option<Duration> {
+        self.media.position
+    }
+
+    /// Find the chapter after the current one
+    pub fn next_chapter_path<'a>(
+        &self,
+        settings: &Settings,
+    ) -> impl Future<Output = Result<PathBuf>> + 'a {
+        self.media.next_chapter_path(settings)
+    }
+
+    /// Find the chapter before the current one
+    pub fn prev_chapter_path<'a>(
+        &self,
+        settings: &Settings,
+    ) -> impl Future<Output = Result<PathBuf>> + 'a {
+        self.media.prev_chapter_path(settings)
+    }
+
+    /// Patterns for chapter directories in addition to the global ones
+    #[must_use]
+    pub const fn chapter_filter(&self) -> &ChapterFilter {
+        &self.media.chapter_filter
+    }
+
+    fn file_name(&self) -> String {
+        file_name(&self.media.name)
+    }
+
+    /// Set the chapter number and save
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn set_chapter(&mut self, value: u8) -> Result<()> {
+        self.media.chapter = value;
+        self.changed()
+    }
+
+    /// Set the episode number, forget the anchor and the position and save
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn set_episode(&mut self, value: u8) -> Result<()> {
+        self.media.episode = value;
+        self.media.episode_anchor = None;
+        self.media.position = None;
+        self.changed()
+    }
+
+    /// Set the chapter directory, forget the anchor and the position and save
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn set_chapter_path(&mut self, value: impl Into<PathBuf>) -> Result<()> {
+        self.media.chapter_path = value.into();
+        self.media.episode_anchor = None;
+        self.media.position = None;
+        self.changed()
+    }
+
+    /// Set the directory with chapters and save
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn set_series_path(&mut self, value: Option<PathBuf>) -> Result<()> {
+        self.media.series_path = value;
+        self.changed()
+    }
+
+    /// Set the cover image, `None` to find it, and save
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn set_cover(&mut self, value: Option<PathBuf>) -> Result<()> {
+        self.media.cover = value;
+        self.changed()
+    }
+
+    /// Set details of the show and save. They aren't a change made by the
+    /// user, so the changing date is kept
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn set_metadata(&mut self, value: Metadata) -> Result<()> {
+        self.media.metadata = value;
+        self.save()
+    }
+
+    /// Read details of the show for the current paths and save
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub async fn reload_metadata(&mut self) -> Result<()> {
+        let metadata = self.load_metadata().await;
+        self.set_metadata(metadata)
+    }
+
+    /// Set media's chapter filter and save
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn set_chapter_filter(&mut self, value: ChapterFilter) -> Result<()> {
+        self.media.chapter_filter = value;
+        self.changed()
+    }
+
+    /// Remember file of the current episode
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn set_episode_anchor(&mut self, episode: &Episode) -> Result<()> {
+        let anchor = EpisodeAnchor::new(episode);
+        if self.media.episode_anchor.as_ref() == Some(&anchor) {
+            return Ok(());
+        }
+        self.media.episode_anchor = Some(anchor);
+        self.save()
+    }
+
+    /// Move the current episode to the anchored file if it was renumbered
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn reconcile_episode(&mut self, episodes: &[Episode]) -> Result<Reconciliation> {
+        let reconciliation = self.media.reconcile_episode(episodes);
+        if matches!(reconciliation, Reconciliation::Moved { .. }) {
+            self.changed()?;
+        }
+        Ok(reconciliation)
+    }
+
+    /// Set the order of episodes and save
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn set_sort_order(&mut self, value: SortOrder) -> Result<()> {
+        self.media.sort_order = value;
+        self.changed()
+    }
+
+    /// Move to the episode and the position where another player stopped
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn resume_at(&mut self, point: &ResumePoint) -> Result<()> {
+        if point.episode != self.media.episode {
+            self.set_episode(point.episode)?;
+        }
+        self.set_position(Some(point.position))
+    }
+
+    /// Remember where playback of the current episode stopped
+    ///
+    /// # Errors
+    ///
+    /// Fails if the media file can't be written.
+    pub fn set_position(&mut self, value: Option<Duration>) -> Result<()> {
+        if self.media.position == value {
+            return Ok(());
+        }
+        self.media.position = value;
+        self.changed()
+    }
+
+    fn path(&self) -> PathBuf {
+        self.config.path_to_media(self.file_name())
+    }
+}
+
+pub fn file_name(name: impl Display) -> String {
+    format!("{name}.json")
+}
+
+fn find_available_name(path: impl AsRef<Path>) -> String {
+    let path = path.as_ref();
+    let mut i = 1;
+    let mut potential_name = DEFAULT_MEDIA_NAME.to_string();
+    loop {
+        let potential_file_name = file_name(&potential_name);
+        let potential_name_used = path.join(potential_file_name).exists();
+        if !potential_name_used {
+            return potential_name;
+        }
+        potential_name = format!("{DEFAULT_MEDIA_NAME} {i}");
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(name: &str) -> Arc<Config> {
+        let dir = std::env::temp_dir().join(format!("omt-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        Arc::new(Config {
+            data_dir: dir.clone(),
+            config_dir: dir.clone(),
+            archive_cache: dir.clone(),
+            thumbnails: dir,
+        })
+    }
+
+    #[test]
+    fn detached_changes_are_not_saved() {
+        let config = config("detached");
+        let media = MediaHandler::new("Show", config.clone()).unwrap();
+        media.remove_file().unwrap();
+        let mut copy = media.detached();
+        copy.set_episode(3).unwrap();
+        copy.rename("Renamed").unwrap();
+        assert!(!config.path_to_media(file_name("Show")).exists());
+        assert!(!config.path_to_media(file_name("Renamed")).exists());
+        fs::remove_dir_all(&config.data_dir).unwrap();
+    }
+
+    #[test]
+    fn merge_keeps_changes_made_meanwhile() {
+        let config = config("merge");
+        let mut media = MediaHandler::new("Show", config.clone()).unwrap();
+        media.set_position(Some(Duration::from_secs(5))).unwrap();
+        let base = media.detached();
+        let mut changed = base.clone();
+        changed.set_episode(2).unwrap();
+        media.set_cover(Some("cover.jpg".into())).unwrap();
+        media.set_chapter(4).unwrap();
+
+        media.merge(&base, &changed).unwrap();
+        assert_eq!(media.episode(), 2);
+        assert_eq!(media.position(), None);
+        assert_eq!(media.chapter(), 4);
+        assert_eq!(media.cover(), Some(Path::new("cover.jpg")));
+        fs::remove_dir_all(&config.data_dir).unwrap();
+    }
+
+    #[test]
+    fn may_contain_files_of_the_chapter() {
+        let config = config("contain");
+        let mut media = MediaHandler::new("Show", config.clone())
+            .unwrap()
+            .detached();
+        let episode = Path::new("/videos/show/01.mkv");
+        assert!(!media.may_contain(episode));
+
+        media.set_chapter_path("/videos/show").unwrap();
+        assert!(media.may_contain(episode));
+        assert!(!media.may_contain(Path::new("/videos/other/01.mkv")));
+
+        let home = Path::new("~").expand_tilde().unwrap().into_owned();
+        media.set_chapter_path("~/show").unwrap();
+        assert!(media.may_contain(&home.join("show/01.mkv")));
+
+        media.set_chapter_path("/videos/list.m3u").unwrap();
+        assert!(media.may_contain(episode));
+        fs::remove_dir_all(&config.data_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn metadata_is_read_on_reload_only() {
+        let config = config("metadata");
+        let chapter_path = config.data_dir.join("Show/Season 1");
+        fs::create_dir_all(&chapter_path).unwrap();
+        let nfo = "<tvshow><title>Show</title></tvshow>";
+        fs::write(config.data_dir.join("Show/tvshow.nfo"), nfo).unwrap();
+        let mut media = MediaHandler::new("Show", config.clone()).unwrap();
+        let base = media.detached();
+
+        media.set_chapter_path(&chapter_path).unwrap();
+        assert_eq!(media.metadata().title, None);
+        let mut changed = media.detached();
+        changed.reload_metadata().await.unwrap();
+        assert_eq!(changed.metadata().title.as_deref(), Some("Show"));
+
+        media.merge(&base, &changed).unwrap();
+        assert_eq!(media.metadata().title.as_deref(), Some("Show"));
+        fs::remove_dir_all(&config.data_dir).unwrap();
+    }
+}
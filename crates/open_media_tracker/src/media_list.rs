@@ -0,0 +1,128 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Arc;
+
+use derive_more::derive::{Deref, DerefMut, From};
+
+use crate::error::{ErrorKind, Result};
+use crate::utils::read_dir;
+use crate::{Config, MediaHandler};
+
+/// Media list borrowed for reading
+pub type MediaListRef<'a> = &'a [MediaHandler];
+/// Media list borrowed for changing
+pub type MediaListRefMut<'a> = &'a mut [MediaHandler];
+
+/// All media of the user
+#[derive(Deref, DerefMut, Debug, Clone, From, Default)]
+pub struct MediaList(Vec<MediaHandler>);
+
+impl MediaList {
+    /// Empty media list
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Remove media along with its file
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be removed.
+    pub fn remove(&mut self, id: usize) -> Result<()> {
+        let media = &self.0[id];
+        media.remove_file()?;
+        self.0.remove(id);
+        Ok(())
+    }
+
+    /// Read all media from the data directory. Media which failed to read
+    /// is skipped and the last error is returned along with the rest
+    pub async fn read(config: Arc<Config>) -> (Self, Option<ErrorKind>) {
+        let dir_content = match read_dir(&config.data_dir).await {
+            Ok(dir_content) => dir_content,
+            Err(err) => return (Self::new(), Some(err)),
+        };
+        let mut error = None;
+        let mut media_list = Vec::with_capacity(dir_content.len());
+        for entry in dir_content {
+            match MediaHandler::read(&entry, config.clone()).await {
+                Ok(media) => media_list.push(media),
+                Err(err) => error = Some(err),
+            }
+        }
+        (media_list.into(), error)
+    }
+
+    /// Rename media with check on unique
+    ///
+    /// # Errors
+    ///
+    /// Fails if the name is used or the file can't be renamed.
+    pub fn rename_media(&mut self, media_id: usize, new_name: impl Into<String>) -> Result<()> {
+        let new_name = new_name.into();
+        if self.name_is_used(&new_name) {
+            return Err(ErrorKind::media_name_is_used(new_name));
+        }
+        self.0[media_id].rename(new_name)?;
+        Ok(())
+    }
+
+    /// Insert media to the `MediaList` and return its index
+    pub fn insert(&mut self, media: MediaHandler) -> usize {
+        let index = self.0.len();
+        self.0.insert(index, media);
+        index
+    }
+
+    /// Create media with the unique name and return its index
+    ///
+    /// # Errors
+    ///
+    /// Fails if the name is used or the file can't be written.
+    pub fn add(&mut self, name: impl Into<String>, config: Arc<Config>) -> Result<usize> {
+        let name = name.into();
+        if self.name_is_used(&name) {
+            return Err(ErrorKind::media_name_is_used(name));
+        }
+        let media = MediaHandler::new(name, config)?;
+        Ok(self.insert(media))
+    }
+
+    /// Index of the media with the name
+    #[must_use]
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.0.iter().position(|s| s.name == name)
+    }
+
+    fn name_is_used(&self, name: &str) -> bool {
+        self.position(name).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn hidden_media_is_read() {
+        let dir = std::env::temp_dir().join(format!("omt-media-list-{}", std::process::id()));
+        fs_err::create_dir_all(&dir).unwrap();
+        let config = Arc::new(Config {
+            data_dir: dir.clone(),
+            config_dir: dir.clone(),
+            archive_cache: dir.clone(),
+            thumbnails: dir.clone(),
+        });
+        MediaHandler::new(".Show", config.clone()).unwrap();
+
+        let (media_list, error) = MediaList::read(config).await;
+        assert!(error.is_none());
+        assert!(media_list.position(".Show").is_some());
+        fs_err::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Metadata left by Kodi and Jellyfin scrapers: `tvshow.nfo`, `season.nfo`,
+//! NFO files named after episodes and the folder artwork
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use expand_tilde::ExpandTilde;
+use serde::{Deserialize, Serialize};
+
+const SHOW_NFO: &str = "tvshow.nfo";
+const SEASON_NFO: &str = "season.nfo";
+const POSTER_NAMES: &[&str] = &["poster", "folder", "cover"];
+const POSTER_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png"];
+
+/// Details of the show shown along with the media
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// Title of the show
+    pub title: Option<String>,
+    /// Year of the first release
+    pub year: Option<u16>,
+    /// Short description of the show
+    pub plot: Option<String>,
+    /// Cover art of the season or of the whole show
+    pub poster: Option<PathBuf>,
+}
+
+impl Metadata {
+    /// Read the metadata of the chapter. The show is looked for in the
+    /// series directory, in the chapter directory and in its parent, the plot
+    /// of the season is preferred to the plot of the show
+    #[must_use]
+    pub fn read(chapter_path: &Path, series_path: Option<&Path>) -> Self {
+        let chapter_path = chapter_path
+            .expand_tilde()
+            .unwrap_or(Cow::Borrowed(chapter_path));
+        // Playlists and archives share the directory with the season
+        let season_dir = if chapter_path.is_dir() {
+            Some(&*chapter_path)
+        } else {
+            chapter_path.parent()
+        };
+        let series_path = series_path.and_then(|path| path.expand_tilde().ok());
+        let show_dirs: Vec<_> = series_path
+            .as_deref()
+            .into_iter()
+            .chain(season_dir)
+            .chain(season_dir.and_then(Path::parent))
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .collect();
+        let show = show_dirs
+            .iter()
+            .find_map(|dir| read_nfo(&dir.join(SHOW_NFO)))
+            .unwrap_or_default();
+        let season = season_dir
+            .and_then(|dir| read_nfo(&dir.join(SEASON_NFO)))
+            .unwrap_or_default();
+        let poster = season_dir
+            .into_iter()
+            .chain(show_dirs.iter().copied())
+            .find_map(find_poster);
+        Self {
+            title: show.title,
+            year: show.year,
+            plot: season.plot.or(show.plot),
+            poster,
+        }
+    }
+
+    /// Nothing was found
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.title.is_none() && self.year.is_none() && self.plot.is_none() && self.poster.is_none()
+    }
+}
+
+/// Fields shared by NFO files of shows, seasons and episodes
+#[derive(Debug, Default)]
+pub struct Nfo {
+    pub(crate) title: Option<String>,
+    pub(crate) year: Option<u16>,
+    pub(crate) plot: Option<String>,
+}
+
+pub fn is_nfo(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("nfo"))
+}
+
+fn read_nfo(path: &Path) -> Option<Nfo> {
+    let content = std::fs::read_to_string(path).ok()?;
+    parse_nfo(&content)
+}
+
+/// Parse the XML of the NFO file. Scrapers may add an URL of the show after
+/// the XML, it's ignored
+pub fn parse_nfo(content: &str) -> Option<Nfo> {
+    let end = content.rfind('>')?;
+    let document = roxmltree::Document::parse(&content[..=end])
+        .inspect_err(|err| log::debug!("Failed to parse NFO: {err}"))
+        .ok()?;
+    let root = document.root_element();
+    let child_text = |name| {
+        root.children()
+            .find(|child| child.has_tag_name(name))
+            .and_then(|child| child.text())
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+    };
+    let year = child_text("year")
+        .or_else(|| child_text("premiered").and_then(|date| date.get(..4)))
+        .and_then(|year| year.parse().ok());
+    Some(Nfo {
+        title: child_text("title").map(String::from),
+        year,
+        plot: child_text("plot").map(String::from),
+    })
+}
+
+fn find_poster(dir: &Path) -> Option<PathBuf> {
+    POSTER_NAMES
+        .iter()
+        .flat_map(|name| {
+            POSTER_EXTENSIONS
+                .iter()
+                .map(move |extension| dir.join(format!("{name}.{extension}")))
+        })
+        .find(|path| path.is_file())
+}
@@ -0,0 +1,165 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::ffi::OsString;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{self, format_duration};
+use crate::{Episode, ErrorKind, Result, SidecarKind};
+
+/// Program used to watch episodes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Player {
+    /// Program with arguments, quoted like in a shell. Empty means the
+    /// default program of the system
+    #[serde(default)]
+    pub command: String,
+    /// Argument prepended to the path of each subtitle file
+    #[serde(default = "default_subtitle_arg")]
+    pub subtitle_arg: String,
+    /// Argument prepended to the path of each external audio track
+    #[serde(default = "default_audio_arg")]
+    pub audio_arg: String,
+    /// Argument prepended to the start position in seconds
+    #[serde(default = "default_start_arg")]
+    pub start_arg: String,
+}
+
+impl Player {
+    /// Start the player with the episode, its sidecars and start position.
+    /// The default program of the system gets only the file, what it misses
+    /// is returned as a warning
+    ///
+    /// # Errors
+    ///
+    /// Fails if the command can't be parsed or the player can't be started.
+    pub fn play(&self, episode: &Episode) -> Result<Option<PlayWarning>> {
+        let args = self.args()?;
+        let Some((program, args)) = args.split_first() else {
+            utils::open(episode.path())?;
+            return Ok(PlayWarning::for_system_player(episode));
+        };
+        let sidecar_args = episode.sidecars().iter().map(|sidecar| {
+            let arg = match sidecar.kind() {
+                SidecarKind::Subtitle => &self.subtitle_arg,
+                SidecarKind::Audio => &self.audio_arg,
+            };
+            let mut arg = OsString::from(arg);
+            arg.push(sidecar.path());
+            arg
+        });
+        let start_arg = episode
+            .start()
+            .map(|start| format!("{}{:.3}", self.start_arg, start.as_secs_f64()));
+        let mut child = Command::new(program)
+            .args(args)
+            .args(sidecar_args)
+            .args(start_arg)
+            .arg(episode.path())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|source| ErrorKind::run_player(program, source))?;
+        // Reap the process when the player is closed
+        std::thread::spawn(move || child.wait());
+        Ok(None)
+    }
+
+    fn args(&self) -> Result<Vec<String>> {
+        shlex::split(&self.command).ok_or_else(|| ErrorKind::player_command(&self.command))
+    }
+}
+
+/// Part of the episode the default program of the system can't be told about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum PlayWarning {
+    #[display(
+        "The system player starts from the beginning of the file. \
+        Set a player command in the settings to start at {}",
+        format_duration(*_0)
+    )]
+    /// The chapter starts at this position of the file
+    StartIgnored(Duration),
+    #[display(
+        "The system player doesn't get subtitles and audio tracks. \
+        Set a player command in the settings to pass them"
+    )]
+    /// Subtitles or audio tracks were found for the episode
+    SidecarsIgnored,
+}
+
+impl PlayWarning {
+    fn for_system_player(episode: &Episode) -> Option<Self> {
+        if let Some(start) = episode.start().filter(|start| !start.is_zero()) {
+            return Some(Self::StartIgnored(start));
+        }
+        (!episode.sidecars().is_empty()).then_some(Self::SidecarsIgnored)
+    }
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            subtitle_arg: default_subtitle_arg(),
+            audio_arg: default_audio_arg(),
+            start_arg: default_start_arg(),
+        }
+    }
+}
+
+fn default_subtitle_arg() -> String {
+    String::from("--sub-file=")
+}
+
+fn default_audio_arg() -> String {
+    String::from("--audio-file=")
+}
+
+fn default_start_arg() -> String {
+    String::from("--start=")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(command: &str) -> Player {
+        Player {
+            command: command.to_string(),
+            ..Player::default()
+        }
+    }
+
+    #[test]
+    fn quoted_arguments_are_kept_together() {
+        let args = player(r#"mpv --title="My Show" '--profile=big screen'"#)
+            .args()
+            .unwrap();
+        assert_eq!(args, ["mpv", "--title=My Show", "--profile=big screen"]);
+    }
+
+    #[test]
+    fn unbalanced_quotes_are_an_error() {
+        assert!(matches!(
+            player(r#"mpv --title="My Show"#).args(),
+            Err(ErrorKind::PlayerCommand { .. })
+        ));
+    }
+
+    #[test]
+    fn plain_file_needs_no_warning() {
+        assert_eq!(
+            PlayWarning::for_system_player(&Episode::new("Show - 01.mkv")),
+            None
+        );
+    }
+}
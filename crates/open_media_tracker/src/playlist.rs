@@ -0,0 +1,183 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use fs_err as fs;
+use url::Url;
+
+use crate::{ErrorKind, Result};
+
+const PLAYLIST_EXTENSIONS: &[&str] = &["m3u", "m3u8", "pls", "xspf"];
+
+/// File, title and length of the PLS entry
+type PlsEntry<'a> = (Option<&'a str>, Option<&'a str>, Option<&'a str>);
+
+/// Entry of the playlist. Location is a local path or an URL of a stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistEntry {
+    pub(crate) location: PathBuf,
+    pub(crate) title: Option<String>,
+    pub(crate) duration: Option<Duration>,
+}
+
+impl PlaylistEntry {
+    fn new(location: PathBuf, title: Option<&str>, duration: Option<Duration>) -> Self {
+        let title = title
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .map(ToString::to_string);
+        Self {
+            location,
+            title,
+            duration,
+        }
+    }
+}
+
+/// Whether the path is an M3U, PLS or XSPF playlist
+#[must_use]
+pub fn is_playlist(path: &Path) -> bool {
+    let is_playlist_extension = path.extension().is_some_and(|extension| {
+        PLAYLIST_EXTENSIONS
+            .iter()
+            .any(|playlist| extension.eq_ignore_ascii_case(playlist))
+    });
+    is_playlist_extension && !path.is_dir()
+}
+
+/// Location which is not a local file
+pub fn is_remote(location: &Path) -> bool {
+    location.to_string_lossy().contains("://")
+}
+
+/// Read M3U, PLS or XSPF playlist. Relative paths are resolved against
+/// the directory of the playlist
+pub async fn read_playlist(path: &Path) -> Result<Vec<PlaylistEntry>> {
+    let content = fs::tokio::read(path).await?;
+    let content = String::from_utf8_lossy(&content);
+    let content = content.trim_start_matches('\u{feff}');
+    let base_dir = path.parent().unwrap_or(path);
+    let extension = path.extension().unwrap_or_default().to_ascii_lowercase();
+    match extension.to_str() {
+        Some("pls") => Ok(parse_pls(content, base_dir)),
+        Some("xspf") => parse_xspf(content, base_dir)
+            .map_err(|err| ErrorKind::parse_playlist(path, err.to_string())),
+        _ => Ok(parse_m3u(content, base_dir)),
+    }
+}
+
+/// Paths with `#EXTINF:<seconds>,<title>` lines before them
+fn parse_m3u(content: &str, base_dir: &Path) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut info = None;
+    for line in content.lines().map(str::trim) {
+        if let Some(extinf) = line.strip_prefix("#EXTINF:") {
+            let (duration, title) = extinf.split_once(',').unwrap_or((extinf, ""));
+            let duration = duration
+                .split_whitespace()
+                .next()
+                .and_then(|duration| duration.parse().ok())
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+            info = Some((duration, title));
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (duration, title) = info.take().unwrap_or_default();
+        let location = resolve_path(line, base_dir);
+        entries.push(PlaylistEntry::new(location, Some(title), duration));
+    }
+    entries
+}
+
+/// `FileN`, `TitleN` and `LengthN` keys of the `[playlist]` section
+fn parse_pls(content: &str, base_dir: &Path) -> Vec<PlaylistEntry> {
+    let mut entries: BTreeMap<u32, PlsEntry> = BTreeMap::new();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim();
+        let split = |prefix| key.strip_prefix(prefix)?.parse().ok();
+        if let Some(n) = split("file") {
+            entries.entry(n).or_default().0 = Some(value);
+        } else if let Some(n) = split("title") {
+            entries.entry(n).or_default().1 = Some(value);
+        } else if let Some(n) = split("length") {
+            entries.entry(n).or_default().2 = Some(value);
+        }
+    }
+    entries
+        .into_values()
+        .filter_map(|(file, title, length)| {
+            let location = resolve_path(file?, base_dir);
+            let duration = length
+                .and_then(|length| length.parse::<u64>().ok())
+                .map(Duration::from_secs);
+            Some(PlaylistEntry::new(location, title, duration))
+        })
+        .collect()
+}
+
+/// `location`, `title` and `duration` in milliseconds of every `track`
+fn parse_xspf(
+    content: &str,
+    base_dir: &Path,
+) -> std::result::Result<Vec<PlaylistEntry>, roxmltree::Error> {
+    let document = roxmltree::Document::parse(content)?;
+    let base_url = Url::from_directory_path(base_dir).ok();
+    let entries = document
+        .descendants()
+        .filter(|node| node.has_tag_name("track"))
+        .filter_map(|track| {
+            let child_text = |name| {
+                track
+                    .children()
+                    .find(|child| child.has_tag_name(name))
+                    .and_then(|child| child.text())
+                    .map(str::trim)
+            };
+            let location = resolve_uri(child_text("location")?, base_url.as_ref());
+            let duration = child_text("duration")
+                .and_then(|duration| duration.parse().ok())
+                .map(Duration::from_millis);
+            Some(PlaylistEntry::new(location, child_text("title"), duration))
+        })
+        .collect();
+    Ok(entries)
+}
+
+/// Location of M3U and PLS entry: a path or an URL
+fn resolve_path(location: &str, base_dir: &Path) -> PathBuf {
+    match Url::parse(location) {
+        // One letter scheme is a Windows drive
+        Ok(url) if url.scheme().len() > 1 => url_location(&url, location),
+        _ => base_dir.join(location),
+    }
+}
+
+/// Location of XSPF entry, relative URIs are resolved against the playlist
+fn resolve_uri(location: &str, base_url: Option<&Url>) -> PathBuf {
+    let url = base_url.map_or_else(|| Url::parse(location), |base_url| base_url.join(location));
+    url.map_or_else(
+        |_| PathBuf::from(location),
+        |url| url_location(&url, location),
+    )
+}
+
+fn url_location(url: &Url, location: &str) -> PathBuf {
+    if url.scheme() == "file" {
+        return url
+            .to_file_path()
+            .unwrap_or_else(|()| PathBuf::from(location));
+    }
+    PathBuf::from(url.as_str())
+}
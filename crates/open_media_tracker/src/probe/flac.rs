@@ -0,0 +1,106 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::time::Duration;
+
+use super::{be_bytes, from_units, invalid_data, read_array, read_vec};
+
+const STREAMINFO: u8 = 0;
+const PICTURE: u8 = 6;
+
+/// Read duration from the `STREAMINFO` block, which is always the first one
+pub fn duration(reader: &mut impl Read) -> io::Result<Duration> {
+    let header = read_array::<8>(reader)?;
+    if header[..4] != *b"fLaC" || header[4] & 0x7F != STREAMINFO {
+        return Err(invalid_data("no STREAMINFO block"));
+    }
+    let info = read_array::<18>(reader)?;
+    let sample_rate =
+        u32::from(info[10]) << 12 | u32::from(info[11]) << 4 | u32::from(info[12]) >> 4;
+    let total_samples = u64::from(info[13] & 0x0F) << 32
+        | u64::from(u32::from_be_bytes([info[14], info[15], info[16], info[17]]));
+    from_units(total_samples, sample_rate.into())
+}
+
+/// Read the image of the first `PICTURE` block
+pub fn cover<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    if read_array::<4>(reader)? != *b"fLaC" {
+        return Err(invalid_data("no fLaC marker"));
+    }
+    loop {
+        let header = read_array::<4>(reader)?;
+        let len = u32::from_be_bytes([0, header[1], header[2], header[3]]);
+        if header[0] & 0x7F == PICTURE {
+            return picture_data(&read_vec(reader, len.into())?).map(Some);
+        }
+        let is_last = header[0] & 0x80 != 0;
+        if is_last {
+            return Ok(None);
+        }
+        reader.seek(SeekFrom::Current(len.into()))?;
+    }
+}
+
+/// Picture type, MIME type, description, dimensions and colors precede the
+/// image
+fn picture_data(block: &[u8]) -> io::Result<Vec<u8>> {
+    let field = |offset| -> io::Result<usize> {
+        let value = u32::from_be_bytes(be_bytes(block, offset)?);
+        usize::try_from(value).map_err(|_| invalid_data("picture field is too big"))
+    };
+    let description_offset = 8 + field(4)?;
+    let data_len_offset = description_offset + 4 + field(description_offset)? + 16;
+    let data_len = field(data_len_offset)?;
+    block
+        .get(data_len_offset + 4..)
+        .and_then(|data| data.get(..data_len))
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| invalid_data("picture is cut"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// `fLaC` marker followed by the last `STREAMINFO` block
+    fn flac(sample_rate: u32, total_samples: u64) -> Vec<u8> {
+        // Sample rate, channels, bits per sample and total samples
+        let packed = u64::from(sample_rate) << 44 | 1 << 41 | 15 << 36 | total_samples;
+        let mut info = [0; 34];
+        info[10..18].copy_from_slice(&packed.to_be_bytes());
+        [b"fLaC".as_slice(), &[0x80 | STREAMINFO, 0, 0, 34], &info].concat()
+    }
+
+    #[test]
+    fn duration_is_read_from_streaminfo() {
+        let data = flac(44_100, 44_100 * 90 + 22_050);
+        let duration = duration(&mut Cursor::new(data)).unwrap();
+        assert_eq!(duration, Duration::from_millis(90_500));
+    }
+
+    #[test]
+    fn zero_sample_rate_is_an_error() {
+        let data = flac(0, 44_100);
+        assert!(duration(&mut Cursor::new(data)).is_err());
+    }
+
+    #[test]
+    fn truncated_streaminfo_is_an_error() {
+        let mut data = flac(44_100, 44_100);
+        data.truncate(16);
+        assert!(duration(&mut Cursor::new(data)).is_err());
+    }
+
+    #[test]
+    fn missing_marker_is_an_error() {
+        let mut data = flac(44_100, 44_100);
+        data[..4].copy_from_slice(b"OggS");
+        assert!(duration(&mut Cursor::new(data)).is_err());
+    }
+}
@@ -0,0 +1,278 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::time::Duration;
+
+use super::{
+    EmbeddedChapter, MediaInfo, be_bytes, invalid_data, read_array, read_vec, sorted_chapters,
+    title,
+};
+
+const EBML: u64 = 0x1A45_DFA3;
+const SEGMENT: u64 = 0x1853_8067;
+const SEEK_HEAD: u64 = 0x114D_9B74;
+const SEEK: u64 = 0x4DBB;
+const SEEK_ID: u64 = 0x53AB;
+const SEEK_POSITION: u64 = 0x53AC;
+const INFO: u64 = 0x1549_A966;
+const TIMESTAMP_SCALE: u64 = 0x2A_D7B1;
+const DURATION: u64 = 0x4489;
+const CHAPTERS: u64 = 0x1043_A770;
+const EDITION_ENTRY: u64 = 0x45B9;
+const CHAPTER_ATOM: u64 = 0xB6;
+const CHAPTER_TIME_START: u64 = 0x91;
+const CHAPTER_FLAG_HIDDEN: u64 = 0x98;
+const CHAPTER_DISPLAY: u64 = 0x80;
+const CHAP_STRING: u64 = 0x85;
+const CLUSTER: u64 = 0x1F43_B675;
+/// Nanoseconds in a tick if `TimestampScale` is absent
+const DEFAULT_TIMESTAMP_SCALE: u32 = 1_000_000;
+const NANOS_PER_SEC: f64 = 1e9;
+/// Elements which are read whole are small, bigger ones are broken
+const MAX_ELEMENT_LEN: u64 = 16 * 1024 * 1024;
+/// Top level elements read before giving up on a broken file
+const MAX_ELEMENTS: usize = 10_000;
+
+/// Read duration from `Segment/Info` and chapters of the first edition from
+/// `Segment/Chapters`. If chapters are stored after the clusters they are
+/// found through `SeekHead`
+pub fn probe<R: Read + Seek>(reader: &mut R) -> io::Result<MediaInfo> {
+    let (id, size) = read_element_header(reader)?;
+    if id != EBML {
+        return Err(invalid_data("no EBML header"));
+    }
+    skip(reader, size)?;
+    let (id, _) = read_element_header(reader)?;
+    if id != SEGMENT {
+        return Err(invalid_data("no segment"));
+    }
+    let segment_start = reader.stream_position()?;
+    let mut chapters_position = None;
+    let mut seek_followed = false;
+    let mut info = MediaInfo::default();
+    for _ in 0..MAX_ELEMENTS {
+        let Ok((id, size)) = read_element_header(reader) else {
+            break;
+        };
+        match id {
+            SEEK_HEAD if !seek_followed => {
+                chapters_position = find_chapters(&read_element(reader, size)?)?;
+            }
+            INFO => info.duration = Some(read_info(&read_element(reader, size)?)?),
+            CHAPTERS => {
+                info.chapters = read_chapters(&read_element(reader, size)?)?;
+                break;
+            }
+            CLUSTER => {
+                // The seek is followed once and only forward, so a broken
+                // `SeekHead` can't loop
+                let cluster_start = reader.stream_position()?;
+                let Some(position) = chapters_position
+                    .take()
+                    .and_then(|position| segment_start.checked_add(position))
+                    .filter(|&position| position > cluster_start)
+                else {
+                    break;
+                };
+                seek_followed = true;
+                reader.seek(SeekFrom::Start(position))?;
+            }
+            _ => skip(reader, size)?,
+        }
+    }
+    info.chapters = sorted_chapters(info.chapters, info.duration);
+    Ok(info)
+}
+
+/// Position of `Chapters` relative to the segment from `SeekHead`
+fn find_chapters(seek_head: &[u8]) -> io::Result<Option<u64>> {
+    for (id, seek) in elements(seek_head)? {
+        if id != SEEK {
+            continue;
+        }
+        let mut seek_id = None;
+        let mut position = None;
+        for (id, content) in elements(seek)? {
+            match id {
+                SEEK_ID => seek_id = Some(uint(content)?),
+                SEEK_POSITION => position = Some(uint(content)?),
+                _ => {}
+            }
+        }
+        if seek_id == Some(CHAPTERS) {
+            return Ok(position);
+        }
+    }
+    Ok(None)
+}
+
+fn read_info(info: &[u8]) -> io::Result<Duration> {
+    let mut timestamp_scale = DEFAULT_TIMESTAMP_SCALE;
+    let mut duration = None;
+    for (id, content) in elements(info)? {
+        match (id, content.len()) {
+            (TIMESTAMP_SCALE, _) => {
+                timestamp_scale =
+                    u32::try_from(uint(content)?).map_err(|_| invalid_data("huge scale"))?;
+            }
+            (DURATION, 4) => duration = Some(f32::from_be_bytes(be_bytes(content, 0)?).into()),
+            (DURATION, 8) => duration = Some(f64::from_be_bytes(be_bytes(content, 0)?)),
+            _ => {}
+        }
+    }
+    let ticks: f64 = duration.ok_or_else(|| invalid_data("no duration"))?;
+    let secs = ticks * f64::from(timestamp_scale) / NANOS_PER_SEC;
+    Duration::try_from_secs_f64(secs).map_err(|_| invalid_data("invalid duration"))
+}
+
+fn read_chapters(chapters: &[u8]) -> io::Result<Vec<EmbeddedChapter>> {
+    let Some((_, edition)) = elements(chapters)?
+        .into_iter()
+        .find(|(id, _)| *id == EDITION_ENTRY)
+    else {
+        return Ok(Vec::new());
+    };
+    let mut chapters = Vec::new();
+    for (id, atom) in elements(edition)? {
+        if id == CHAPTER_ATOM
+            && let Some(chapter) = read_chapter_atom(atom)?
+        {
+            chapters.push(chapter);
+        }
+    }
+    Ok(chapters)
+}
+
+fn read_chapter_atom(atom: &[u8]) -> io::Result<Option<EmbeddedChapter>> {
+    let mut start = None;
+    let mut hidden = false;
+    let mut chapter_title = String::new();
+    for (id, content) in elements(atom)? {
+        match id {
+            CHAPTER_TIME_START => start = Some(Duration::from_nanos(uint(content)?)),
+            CHAPTER_FLAG_HIDDEN => hidden = uint(content)? != 0,
+            CHAPTER_DISPLAY if chapter_title.is_empty() => {
+                if let Some((_, string)) = elements(content)?
+                    .into_iter()
+                    .find(|(id, _)| *id == CHAP_STRING)
+                {
+                    chapter_title = title(string);
+                }
+            }
+            _ => {}
+        }
+    }
+    let chapter = start.map(|start| EmbeddedChapter {
+        title: chapter_title,
+        start,
+    });
+    Ok(chapter.filter(|_| !hidden))
+}
+
+/// Split element content into child elements
+fn elements(mut data: &[u8]) -> io::Result<Vec<(u64, &[u8])>> {
+    let mut elements = Vec::new();
+    while !data.is_empty() {
+        let (id, size) = read_element_header(&mut data)?;
+        let size = size
+            .and_then(|size| usize::try_from(size).ok())
+            .filter(|&size| size <= data.len())
+            .ok_or_else(|| invalid_data("invalid element size"))?;
+        let (content, rest) = data.split_at(size);
+        elements.push((id, content));
+        data = rest;
+    }
+    Ok(elements)
+}
+
+fn read_element(reader: &mut impl Read, size: Option<u64>) -> io::Result<Vec<u8>> {
+    let size = size
+        .filter(|&size| size <= MAX_ELEMENT_LEN)
+        .ok_or_else(|| invalid_data("invalid element size"))?;
+    read_vec(reader, size)
+}
+
+/// Read element ID and size. Size is `None` if it is unknown
+fn read_element_header(reader: &mut impl Read) -> io::Result<(u64, Option<u64>)> {
+    let (id, _) = read_vint(reader)?;
+    let (size, len) = read_vint(reader)?;
+    let value_bits = 7 * len;
+    let size_mask = (1 << value_bits) - 1;
+    let unknown = size & size_mask == size_mask;
+    Ok((id, (!unknown).then_some(size & size_mask)))
+}
+
+/// Read variable size integer with its length marker and return its length
+fn read_vint(reader: &mut impl Read) -> io::Result<(u64, u32)> {
+    let [first] = read_array(reader)?;
+    let len = first.leading_zeros() + 1;
+    if len > 8 {
+        return Err(invalid_data("invalid variable size integer"));
+    }
+    let mut value = u64::from(first);
+    for _ in 1..len {
+        let [byte] = read_array(reader)?;
+        value = value << 8 | u64::from(byte);
+    }
+    Ok((value, len))
+}
+
+fn uint(data: &[u8]) -> io::Result<u64> {
+    if data.len() > 8 {
+        return Err(invalid_data("integer is too long"));
+    }
+    Ok(data
+        .iter()
+        .fold(0, |value, &byte| value << 8 | u64::from(byte)))
+}
+
+fn skip(reader: &mut impl Seek, size: Option<u64>) -> io::Result<()> {
+    let size = size.ok_or_else(|| invalid_data("unknown size"))?;
+    let offset = i64::try_from(size).map_err(|_| invalid_data("huge element"))?;
+    reader.seek(SeekFrom::Current(offset))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Element with 8 bytes long size
+    fn element(id: u64, content: &[u8]) -> Vec<u8> {
+        let id_bytes = id.to_be_bytes();
+        let first = id_bytes.iter().position(|&byte| byte != 0).unwrap_or(7);
+        let mut data = id_bytes[first..].to_vec();
+        data.push(0x01);
+        data.extend(&(content.len() as u64).to_be_bytes()[1..]);
+        data.extend(content);
+        data
+    }
+
+    #[test]
+    fn seek_back_is_not_followed() {
+        let seek = [
+            element(SEEK_ID, &CHAPTERS.to_be_bytes()[4..]),
+            element(SEEK_POSITION, &[0]),
+        ]
+        .concat();
+        let info = element(DURATION, &90_000_f64.to_be_bytes());
+        let segment = [
+            element(SEEK_HEAD, &element(SEEK, &seek)),
+            element(INFO, &info),
+            element(CLUSTER, &[0; 16]),
+        ]
+        .concat();
+        let mut data = element(EBML, &[]);
+        data.extend(&SEGMENT.to_be_bytes()[4..]);
+        data.extend([0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        data.extend(segment);
+        let info = probe(&mut Cursor::new(data)).unwrap();
+        assert_eq!(info.duration, Some(Duration::from_secs(90)));
+    }
+}
@@ -0,0 +1,179 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Reading of the media duration, chapter markers and cover art from
+//! container headers
+
+mod flac;
+mod mkv;
+mod mp3;
+mod mp4;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+/// Chapter titles are short, longer ones are cut
+const MAX_TITLE_LEN: usize = 1024;
+
+type Cache = HashMap<PathBuf, (SystemTime, MediaInfo)>;
+
+static CACHE: LazyLock<Mutex<Cache>> = LazyLock::new(Mutex::default);
+
+/// Chapter marker inside a media file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedChapter {
+    pub(crate) title: String,
+    pub(crate) start: Duration,
+}
+
+/// What is known about the media file from its headers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MediaInfo {
+    pub(crate) duration: Option<Duration>,
+    pub(crate) chapters: Vec<EmbeddedChapter>,
+}
+
+impl From<Duration> for MediaInfo {
+    fn from(duration: Duration) -> Self {
+        Self {
+            duration: Some(duration),
+            chapters: Vec::new(),
+        }
+    }
+}
+
+/// Duration and chapters of MP4, Matroska, MP3 or FLAC file. Results are
+/// cached by the path and the modification time of the file. Headers are
+/// read in a blocking thread
+pub async fn probe_media(path: PathBuf, modified: Option<SystemTime>) -> MediaInfo {
+    tokio::task::spawn_blocking(move || probe_cached(&path, modified))
+        .await
+        .inspect_err(|err| log::debug!("failed to probe: {err}"))
+        .unwrap_or_default()
+}
+
+fn probe_cached(path: &Path, modified: Option<SystemTime>) -> MediaInfo {
+    let Some(modified) = modified else {
+        return probe(path);
+    };
+    let cached = CACHE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(path)
+        .cloned();
+    if let Some((cached_modified, info)) = cached
+        && cached_modified == modified
+    {
+        return info;
+    }
+    let info = probe(path);
+    CACHE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(path.to_path_buf(), (modified, info.clone()));
+    info
+}
+
+fn probe(path: &Path) -> MediaInfo {
+    let Ok(file) = File::open(path) else {
+        return MediaInfo::default();
+    };
+    let mut reader = BufReader::new(file);
+    let mut magic = [0; 8];
+    if reader.read_exact(&mut magic).is_err() || reader.seek(SeekFrom::Start(0)).is_err() {
+        return MediaInfo::default();
+    }
+    let info = match magic {
+        [_, _, _, _, b'f', b't', b'y', b'p'] => mp4::probe(&mut reader),
+        [0x1A, 0x45, 0xDF, 0xA3, ..] => mkv::probe(&mut reader),
+        [b'f', b'L', b'a', b'C', ..] => flac::duration(&mut reader).map(MediaInfo::from),
+        [b'I', b'D', b'3', ..] => mp3::probe(&mut reader),
+        [0xFF, second, ..] if mp3::is_frame_sync(second) => mp3::probe(&mut reader),
+        _ => return MediaInfo::default(),
+    };
+    info.inspect_err(|err| log::debug!("{}: failed to probe: {err}", path.display()))
+        .unwrap_or_default()
+}
+
+/// Cover image embedded into MP4, MP3 or FLAC file
+pub fn embedded_cover(path: &Path) -> Option<Vec<u8>> {
+    let mut reader = BufReader::new(File::open(path).ok()?);
+    let magic = read_array::<8>(&mut reader).ok()?;
+    reader.seek(SeekFrom::Start(0)).ok()?;
+    let cover = match magic {
+        [_, _, _, _, b'f', b't', b'y', b'p'] => mp4::cover(&mut reader),
+        [b'f', b'L', b'a', b'C', ..] => flac::cover(&mut reader),
+        [b'I', b'D', b'3', ..] => mp3::cover(&mut reader),
+        _ => return None,
+    };
+    cover
+        .inspect_err(|err| log::debug!("{}: failed to read cover: {err}", path.display()))
+        .ok()
+        .flatten()
+}
+
+/// Duration of `units` when there is `per_second` of them in a second
+fn from_units(units: u64, per_second: u64) -> io::Result<Duration> {
+    if per_second == 0 {
+        return Err(invalid_data("zero time scale"));
+    }
+    let secs = Duration::from_secs(units / per_second);
+    let nanos = Duration::from_nanos(units % per_second * NANOS_PER_SEC / per_second);
+    Ok(secs + nanos)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_vec(reader: &mut impl Read, len: u64) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    reader.take(len).read_to_end(&mut buf)?;
+    if buf.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(buf)
+}
+
+/// Bytes of the big-endian number at `offset`
+fn be_bytes<const N: usize>(data: &[u8], offset: usize) -> io::Result<[u8; N]> {
+    data.get(offset..)
+        .and_then(|data| data.get(..N))
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid_data("unexpected end of data"))
+}
+
+fn title(bytes: &[u8]) -> String {
+    let bytes = &bytes[..bytes.len().min(MAX_TITLE_LEN)];
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches('\0')
+        .trim()
+        .to_string()
+}
+
+/// Sort chapters and drop the ones starting after the end of the file
+fn sorted_chapters(
+    mut chapters: Vec<EmbeddedChapter>,
+    duration: Option<Duration>,
+) -> Vec<EmbeddedChapter> {
+    chapters.sort_by_key(|chapter| chapter.start);
+    chapters.dedup_by_key(|chapter| chapter.start);
+    if let Some(duration) = duration {
+        chapters.retain(|chapter| chapter.start < duration);
+    }
+    chapters
+}
@@ -0,0 +1,437 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::time::Duration;
+
+use super::{
+    EmbeddedChapter, MediaInfo, be_bytes, from_units, invalid_data, read_array, read_vec,
+    sorted_chapters, title,
+};
+
+/// How far the first frame is searched after the ID3 tag
+const SEARCH_LEN: usize = 64 * 1024;
+const ID3_HEADER_LEN: u64 = 10;
+const FRAME_HEADER_LEN: usize = 4;
+const ID3_FRAME_HEADER_LEN: usize = 10;
+/// Offset of the VBRI header from the frame header
+const VBRI_OFFSET: usize = FRAME_HEADER_LEN + 32;
+const BITRATES_V1_L1: [u32; 15] = [
+    0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+];
+const BITRATES_V1_L2: [u32; 15] = [
+    0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+];
+const BITRATES_V1_L3: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const BITRATES_V2_L1: [u32; 15] = [
+    0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+];
+const BITRATES_V2_L23: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+pub const fn is_frame_sync(second_byte: u8) -> bool {
+    second_byte & 0xE0 == 0xE0
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Version {
+    V1,
+    V2,
+    V25,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Layer {
+    L1,
+    L2,
+    L3,
+}
+
+struct FrameHeader {
+    version: Version,
+    layer: Layer,
+    /// Bits per second
+    bitrate: u32,
+    sample_rate: u32,
+    mono: bool,
+}
+
+impl FrameHeader {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        let &[0xFF, b1, b2, b3, ..] = bytes else {
+            return None;
+        };
+        if !is_frame_sync(b1) {
+            return None;
+        }
+        let version = match (b1 >> 3) & 0b11 {
+            0 => Version::V25,
+            2 => Version::V2,
+            3 => Version::V1,
+            _ => return None,
+        };
+        let layer = match (b1 >> 1) & 0b11 {
+            1 => Layer::L3,
+            2 => Layer::L2,
+            3 => Layer::L1,
+            _ => return None,
+        };
+        let bitrates = match (version, layer) {
+            (Version::V1, Layer::L1) => &BITRATES_V1_L1,
+            (Version::V1, Layer::L2) => &BITRATES_V1_L2,
+            (Version::V1, Layer::L3) => &BITRATES_V1_L3,
+            (_, Layer::L1) => &BITRATES_V2_L1,
+            (_, _) => &BITRATES_V2_L23,
+        };
+        let bitrate = *bitrates.get(usize::from(b2 >> 4))? * 1000;
+        let sample_rate = match (b2 >> 2) & 0b11 {
+            0 => 44100,
+            1 => 48000,
+            2 => 32000,
+            _ => return None,
+        };
+        let sample_rate = match version {
+            Version::V1 => sample_rate,
+            Version::V2 => sample_rate / 2,
+            Version::V25 => sample_rate / 4,
+        };
+        let mono = b3 >> 6 == 0b11;
+        Some(Self {
+            version,
+            layer,
+            bitrate,
+            sample_rate,
+            mono,
+        })
+    }
+
+    const fn samples_per_frame(&self) -> u64 {
+        match (self.layer, self.version) {
+            (Layer::L1, _) => 384,
+            (Layer::L2, _) | (Layer::L3, Version::V1) => 1152,
+            (Layer::L3, _) => 576,
+        }
+    }
+
+    /// Offset of the Xing header from the frame header
+    const fn xing_offset(&self) -> usize {
+        let side_info_len = match (self.version, self.mono) {
+            (Version::V1, false) => 32,
+            (Version::V1, true) | (_, false) => 17,
+            (_, true) => 9,
+        };
+        FRAME_HEADER_LEN + side_info_len
+    }
+}
+
+/// Read duration and chapters from `ID3v2` `CHAP` frames
+pub fn probe<R: Read + Seek>(reader: &mut R) -> io::Result<MediaInfo> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+    let id3_header = read_array::<10>(reader)?;
+    let mut chapters = Vec::new();
+    let audio_start = if id3_header[..3] == *b"ID3" {
+        let size = synchsafe(&id3_header[6..]);
+        let has_footer = id3_header[5] & 0x10 != 0;
+        chapters = read_chapters(reader, &id3_header, size)
+            .inspect_err(|err| log::debug!("failed to read chapters: {err}"))
+            .unwrap_or_default();
+        ID3_HEADER_LEN + size + if has_footer { ID3_HEADER_LEN } else { 0 }
+    } else {
+        0
+    };
+    let duration = duration(reader, audio_start, file_len)?;
+    Ok(MediaInfo {
+        duration: Some(duration),
+        chapters: sorted_chapters(chapters, Some(duration)),
+    })
+}
+
+/// Count frames by the Xing or VBRI header of variable bitrate files, use
+/// the bitrate of the first frame otherwise
+fn duration<R: Read + Seek>(
+    reader: &mut R,
+    audio_start: u64,
+    file_len: u64,
+) -> io::Result<Duration> {
+    reader.seek(SeekFrom::Start(audio_start))?;
+    let mut buf = Vec::with_capacity(SEARCH_LEN);
+    reader.take(SEARCH_LEN as u64).read_to_end(&mut buf)?;
+    let (position, header) = (0..buf.len())
+        .find_map(|i| FrameHeader::parse(&buf[i..]).map(|header| (i, header)))
+        .ok_or_else(|| invalid_data("no MPEG audio frame"))?;
+    let frame = &buf[position..];
+    if let Some(frames) = xing_frames(frame, &header).or_else(|| vbri_frames(frame)) {
+        return from_units(
+            frames * header.samples_per_frame(),
+            header.sample_rate.into(),
+        );
+    }
+    let audio_len = file_len.saturating_sub(audio_start + position as u64);
+    from_units(audio_len * 8, header.bitrate.into())
+}
+
+/// Read the image from the first `APIC` frame of `ID3v2` tag
+pub fn cover<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let id3_header = read_array::<10>(reader)?;
+    if id3_header[..3] != *b"ID3" {
+        return Ok(None);
+    }
+    let size = synchsafe(&id3_header[6..]);
+    let frames = read_frames(reader, &id3_header, size, *b"APIC")?;
+    Ok(frames.first().and_then(|frame| apic_data(frame)))
+}
+
+/// Parse `CHAP` frames of the tag
+fn read_chapters<R: Read + Seek>(
+    reader: &mut R,
+    id3_header: &[u8; 10],
+    tag_len: u64,
+) -> io::Result<Vec<EmbeddedChapter>> {
+    let version = id3_header[3];
+    let mut chapters = Vec::new();
+    for frame in read_frames(reader, id3_header, tag_len, *b"CHAP")? {
+        if let Some(chapter) = parse_chap(&frame, version)? {
+            chapters.push(chapter);
+        }
+    }
+    Ok(chapters)
+}
+
+/// Walk frames of `ID3v2.3` or `ID3v2.4` tag and read the ones with `wanted`
+/// ID
+fn read_frames<R: Read + Seek>(
+    reader: &mut R,
+    id3_header: &[u8; 10],
+    tag_len: u64,
+    wanted: [u8; 4],
+) -> io::Result<Vec<Vec<u8>>> {
+    let version = id3_header[3];
+    if !matches!(version, 3 | 4) {
+        return Ok(Vec::new());
+    }
+    let has_extended_header = id3_header[5] & 0x40 != 0;
+    if has_extended_header {
+        let size = read_array::<4>(reader)?;
+        let skip = if version == 4 {
+            synchsafe(&size).saturating_sub(4)
+        } else {
+            u32::from_be_bytes(size).into()
+        };
+        reader.seek(SeekFrom::Current(i64::try_from(skip).unwrap_or(i64::MAX)))?;
+    }
+    let tag_end = ID3_HEADER_LEN + tag_len;
+    let mut frames = Vec::new();
+    while reader.stream_position()? + ID3_FRAME_HEADER_LEN as u64 <= tag_end {
+        let frame_header = read_array::<10>(reader)?;
+        let Some((id, size)) = frame_header_fields(&frame_header, version) else {
+            break;
+        };
+        if id == wanted {
+            frames.push(read_vec(reader, size)?);
+        } else {
+            reader.seek(SeekFrom::Current(i64::try_from(size).unwrap_or(i64::MAX)))?;
+        }
+    }
+    Ok(frames)
+}
+
+/// `APIC` frame: text encoding, MIME type, picture type and description
+/// precede the image
+fn apic_data(frame: &[u8]) -> Option<Vec<u8>> {
+    let (&encoding, rest) = frame.split_first()?;
+    let mime_end = rest.iter().position(|&byte| byte == 0)?;
+    let description = rest.get(mime_end + 2..)?;
+    // UTF-16 descriptions end with two zero bytes
+    let description_end = if matches!(encoding, 1 | 2) {
+        description
+            .chunks_exact(2)
+            .position(|pair| pair == [0, 0])?
+            * 2
+            + 2
+    } else {
+        description.iter().position(|&byte| byte == 0)? + 1
+    };
+    description.get(description_end..).map(<[u8]>::to_vec)
+}
+
+/// Frame ID and size, `None` for the padding
+fn frame_header_fields(header: &[u8; 10], version: u8) -> Option<([u8; 4], u64)> {
+    let id: [u8; 4] = header[..4].try_into().ok()?;
+    if !id.iter().all(u8::is_ascii_alphanumeric) {
+        return None;
+    }
+    let size = if version == 4 {
+        synchsafe(&header[4..8])
+    } else {
+        u32::from_be_bytes(header[4..8].try_into().ok()?).into()
+    };
+    Some((id, size))
+}
+
+/// `CHAP` frame: element ID, start and end times in milliseconds, byte
+/// offsets and subframes with the title
+fn parse_chap(frame: &[u8], version: u8) -> io::Result<Option<EmbeddedChapter>> {
+    let Some(id_end) = frame.iter().position(|&byte| byte == 0) else {
+        return Ok(None);
+    };
+    let start_ms = u32::from_be_bytes(be_bytes(frame, id_end + 1)?);
+    let mut subframes = frame.get(id_end + 17..).unwrap_or_default();
+    let mut chapter_title = String::new();
+    while subframes.len() >= ID3_FRAME_HEADER_LEN {
+        let (header, rest) = subframes.split_at(ID3_FRAME_HEADER_LEN);
+        let Some((id, size)) = header
+            .try_into()
+            .ok()
+            .and_then(|header| frame_header_fields(header, version))
+        else {
+            break;
+        };
+        let size = usize::try_from(size).unwrap_or(usize::MAX).min(rest.len());
+        let (content, rest) = rest.split_at(size);
+        if id == *b"TIT2" {
+            chapter_title = text_frame(content);
+            break;
+        }
+        subframes = rest;
+    }
+    Ok(Some(EmbeddedChapter {
+        title: chapter_title,
+        start: Duration::from_millis(start_ms.into()),
+    }))
+}
+
+/// Decode text frame content according to its encoding byte
+fn text_frame(content: &[u8]) -> String {
+    let Some((&encoding, text)) = content.split_first() else {
+        return String::new();
+    };
+    let utf16 = |text: &[u8], big_endian: bool| {
+        let units: Vec<u16> = text
+            .chunks_exact(2)
+            .map(|pair| {
+                let pair = [pair[0], pair[1]];
+                if big_endian {
+                    u16::from_be_bytes(pair)
+                } else {
+                    u16::from_le_bytes(pair)
+                }
+            })
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+    let text = match (encoding, text) {
+        (0, text) => text.iter().map(|&byte| char::from(byte)).collect(),
+        (1, [0xFF, 0xFE, text @ ..]) => utf16(text, false),
+        (1 | 2, [0xFE, 0xFF, text @ ..]) | (2, text) => utf16(text, true),
+        (_, text) => return title(text),
+    };
+    title(text.as_bytes())
+}
+
+/// Integer with 7 significant bits in every byte
+fn synchsafe(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |size, &byte| size << 7 | u64::from(byte & 0x7F))
+}
+
+fn xing_frames(frame: &[u8], header: &FrameHeader) -> Option<u64> {
+    let xing = frame.get(header.xing_offset()..)?;
+    if !xing.starts_with(b"Xing") && !xing.starts_with(b"Info") {
+        return None;
+    }
+    let flags = read_u32(xing.get(4..)?)?;
+    let has_frames = flags & 1 != 0;
+    has_frames
+        .then(|| read_u32(xing.get(8..)?))
+        .flatten()
+        .map(u64::from)
+}
+
+fn vbri_frames(frame: &[u8]) -> Option<u64> {
+    let vbri = frame.get(VBRI_OFFSET..)?;
+    if !vbri.starts_with(b"VBRI") {
+        return None;
+    }
+    read_u32(vbri.get(14..)?).map(u64::from)
+}
+
+fn read_u32(bytes: &[u8]) -> Option<u32> {
+    let bytes = bytes.get(..4)?.try_into().ok()?;
+    Some(u32::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// MPEG-1 Layer III, 128 kbit/s, 44.1 kHz, stereo
+    const HEADER: [u8; 4] = [0xFF, 0xFB, 0x90, 0x00];
+    /// Frames which last exactly 32 seconds
+    const FRAMES: u32 = 1225;
+
+    /// Frame with the tag at the offset of the Xing and VBRI headers
+    fn frame(tag: &[u8]) -> Vec<u8> {
+        let mut data = HEADER.to_vec();
+        data.resize(VBRI_OFFSET, 0);
+        data.extend(tag);
+        data.resize(1024, 0);
+        data
+    }
+
+    #[test]
+    fn xing_header_counts_frames() {
+        let tag = [
+            b"Xing".as_slice(),
+            &1_u32.to_be_bytes(),
+            &FRAMES.to_be_bytes(),
+        ]
+        .concat();
+        let info = probe(&mut Cursor::new(frame(&tag))).unwrap();
+        assert_eq!(info.duration, Some(Duration::from_secs(32)));
+    }
+
+    #[test]
+    fn vbri_header_counts_frames() {
+        let tag = [b"VBRI".as_slice(), &[0; 10], &FRAMES.to_be_bytes()].concat();
+        let info = probe(&mut Cursor::new(frame(&tag))).unwrap();
+        assert_eq!(info.duration, Some(Duration::from_secs(32)));
+    }
+
+    #[test]
+    fn constant_bitrate_is_estimated_by_file_size() {
+        let mut data = frame(&[]);
+        // One second at 128 kbit/s
+        data.resize(16_000, 0);
+        let info = probe(&mut Cursor::new(data)).unwrap();
+        assert_eq!(info.duration, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn xing_header_without_frame_count_falls_back_to_bitrate() {
+        let mut data = frame(&[b"Xing".as_slice(), &0_u32.to_be_bytes()].concat());
+        data.resize(16_000, 0);
+        let info = probe(&mut Cursor::new(data)).unwrap();
+        assert_eq!(info.duration, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn free_bitrate_is_an_error() {
+        let mut data = frame(&[]);
+        data[2] = 0x00;
+        assert!(probe(&mut Cursor::new(data)).is_err());
+    }
+
+    #[test]
+    fn truncated_file_is_an_error() {
+        assert!(probe(&mut Cursor::new(HEADER.to_vec())).is_err());
+        assert!(probe(&mut Cursor::new(vec![0; 32])).is_err());
+    }
+}
@@ -0,0 +1,383 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+use super::{
+    EmbeddedChapter, MediaInfo, be_bytes, from_units, invalid_data, read_array, read_vec,
+    sorted_chapters, title,
+};
+
+/// Nero chapter start times are in 100 ns units
+const NERO_UNITS_PER_SEC: u64 = 10_000_000;
+/// Boxes which are read whole are small, bigger ones are broken
+const MAX_BOX_LEN: u64 = 16 * 1024 * 1024;
+const MAX_CHAPTERS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Mp4Box {
+    name: [u8; 4],
+    /// Beginning of the box content
+    start: u64,
+    end: u64,
+}
+
+/// Read duration from `moov/mvhd` and chapters from Nero `moov/udta/chpl` box
+/// or `QuickTime` chapter track
+pub fn probe<R: Read + Seek>(reader: &mut R) -> io::Result<MediaInfo> {
+    let file_end = reader.seek(SeekFrom::End(0))?;
+    let top = children(reader, 0, file_end)?;
+    let moov = find(&top, *b"moov").ok_or_else(|| invalid_data("no moov box"))?;
+    let moov = children(reader, moov.start, moov.end)?;
+    let mvhd = find(&moov, *b"mvhd").ok_or_else(|| invalid_data("no mvhd box"))?;
+    let (timescale, duration) = time_header(&read_box(reader, mvhd)?)?;
+    let duration = from_units(duration, timescale.into())?;
+    let chapters = match nero_chapters(reader, &moov) {
+        Ok(chapters) if !chapters.is_empty() => Ok(chapters),
+        _ => quicktime_chapters(reader, &moov),
+    }
+    .inspect_err(|err| log::debug!("failed to read chapters: {err}"))
+    .unwrap_or_default();
+    Ok(MediaInfo {
+        duration: Some(duration),
+        chapters: sorted_chapters(chapters, Some(duration)),
+    })
+}
+
+/// Read the image from iTunes `moov/udta/meta/ilst/covr` box
+pub fn cover<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let file_end = reader.seek(SeekFrom::End(0))?;
+    let top = children(reader, 0, file_end)?;
+    let moov = find(&top, *b"moov").ok_or_else(|| invalid_data("no moov box"))?;
+    let moov = children(reader, moov.start, moov.end)?;
+    let Some(meta) = find_path(reader, &moov, &[*b"udta", *b"meta"])? else {
+        return Ok(None);
+    };
+    // Version and flags of `meta` precede its children
+    let meta = children(reader, meta.start + 4, meta.end)?;
+    let Some(data) = find_path(reader, &meta, &[*b"ilst", *b"covr", *b"data"])? else {
+        return Ok(None);
+    };
+    // Type and locale of `data` precede the image
+    Ok(read_box(reader, &data)?.get(8..).map(<[u8]>::to_vec))
+}
+
+fn nero_chapters<R: Read + Seek>(
+    reader: &mut R,
+    moov: &[Mp4Box],
+) -> io::Result<Vec<EmbeddedChapter>> {
+    let Some(chpl) = find_path(reader, moov, &[*b"udta", *b"chpl"])? else {
+        return Ok(Vec::new());
+    };
+    let data = read_box(reader, &chpl)?;
+    let [version, ..] = be_bytes::<1>(&data, 0)?;
+    let mut offset = if version == 0 { 4 } else { 8 };
+    let [count] = be_bytes(&data, offset)?;
+    offset += 1;
+    let mut chapters = Vec::with_capacity(count.into());
+    for _ in 0..count {
+        let start = u64::from_be_bytes(be_bytes(&data, offset)?);
+        let [title_len] = be_bytes(&data, offset + 8)?;
+        offset += 9;
+        let title_bytes = data
+            .get(offset..offset + usize::from(title_len))
+            .ok_or_else(|| invalid_data("chapter title is cut"))?;
+        offset += usize::from(title_len);
+        chapters.push(EmbeddedChapter {
+            title: title(title_bytes),
+            start: from_units(start, NERO_UNITS_PER_SEC)?,
+        });
+    }
+    Ok(chapters)
+}
+
+/// Chapters stored as a text track referenced by `tref/chap` of another track
+fn quicktime_chapters<R: Read + Seek>(
+    reader: &mut R,
+    moov: &[Mp4Box],
+) -> io::Result<Vec<EmbeddedChapter>> {
+    let traks: Vec<_> = moov.iter().filter(|b| b.name == *b"trak").collect();
+    let mut chapter_track_id = None;
+    for trak in &traks {
+        let trak = children(reader, trak.start, trak.end)?;
+        if let Some(chap) = find_path(reader, &trak, &[*b"tref", *b"chap"])? {
+            chapter_track_id = Some(u32::from_be_bytes(be_bytes(&read_box(reader, &chap)?, 0)?));
+            break;
+        }
+    }
+    let Some(chapter_track_id) = chapter_track_id else {
+        return Ok(Vec::new());
+    };
+    for trak in traks {
+        let trak = children(reader, trak.start, trak.end)?;
+        let Some(tkhd) = find(&trak, *b"tkhd") else {
+            continue;
+        };
+        let tkhd = read_box(reader, tkhd)?;
+        let id_offset = if tkhd.first() == Some(&1) { 20 } else { 12 };
+        if u32::from_be_bytes(be_bytes(&tkhd, id_offset)?) == chapter_track_id {
+            return text_track(reader, &trak);
+        }
+    }
+    Ok(Vec::new())
+}
+
+/// Read every sample of the text track as a chapter title
+fn text_track<R: Read + Seek>(reader: &mut R, trak: &[Mp4Box]) -> io::Result<Vec<EmbeddedChapter>> {
+    let mdhd = find_path(reader, trak, &[*b"mdia", *b"mdhd"])?
+        .ok_or_else(|| invalid_data("no mdhd box"))?;
+    let (timescale, _) = time_header(&read_box(reader, &mdhd)?)?;
+    let stbl = find_path(reader, trak, &[*b"mdia", *b"minf", *b"stbl"])?
+        .ok_or_else(|| invalid_data("no stbl box"))?;
+    let stbl = children(reader, stbl.start, stbl.end)?;
+    let read_child = |reader: &mut R, name: [u8; 4]| -> io::Result<Vec<u8>> {
+        let child = find(&stbl, name).ok_or_else(|| invalid_data("incomplete sample table"))?;
+        read_box(reader, child)
+    };
+    let starts = sample_starts(&read_child(reader, *b"stts")?)?;
+    let sizes = sample_sizes(&read_child(reader, *b"stsz")?)?;
+    let chunk_offsets = match find(&stbl, *b"co64") {
+        Some(co64) => table(&read_box(reader, co64)?, 8, |entry| {
+            Ok(u64::from_be_bytes(be_bytes(entry, 0)?))
+        })?,
+        None => table(&read_child(reader, *b"stco")?, 4, |entry| {
+            Ok(u32::from_be_bytes(be_bytes(entry, 0)?).into())
+        })?,
+    };
+    let sample_to_chunk = table(&read_child(reader, *b"stsc")?, 12, |entry| {
+        let first_chunk = u32::from_be_bytes(be_bytes(entry, 0)?);
+        let samples_per_chunk = u32::from_be_bytes(be_bytes(entry, 4)?);
+        Ok((first_chunk, samples_per_chunk))
+    })?;
+
+    // Runs of `stsc` are sorted by their first chunk, so they are walked
+    // along with the chunks
+    let mut runs = sample_to_chunk.into_iter().peekable();
+    let mut samples_per_chunk = 0;
+    let mut offsets = Vec::with_capacity(sizes.len());
+    for (chunk, chunk_offset) in (1..).zip(chunk_offsets) {
+        while let Some((_, samples)) = runs.next_if(|(first_chunk, _)| *first_chunk <= chunk) {
+            samples_per_chunk = samples;
+        }
+        let mut offset = chunk_offset;
+        for _ in 0..samples_per_chunk {
+            let Some(size) = sizes.get(offsets.len()) else {
+                break;
+            };
+            offsets.push(offset);
+            offset = offset
+                .checked_add(u64::from(*size))
+                .ok_or_else(|| invalid_data("sample offset overflow"))?;
+        }
+    }
+
+    let mut chapters = Vec::with_capacity(offsets.len());
+    for (offset, start) in offsets.into_iter().zip(starts).take(MAX_CHAPTERS) {
+        reader.seek(SeekFrom::Start(offset))?;
+        let title_len = u16::from_be_bytes(read_array(reader)?);
+        let title_bytes = read_vec(reader, title_len.into())?;
+        chapters.push(EmbeddedChapter {
+            title: title(&title_bytes),
+            start: from_units(start, timescale.into())?,
+        });
+    }
+    Ok(chapters)
+}
+
+/// Start of every sample in the track time scale from `stts` box
+fn sample_starts(stts: &[u8]) -> io::Result<Vec<u64>> {
+    let entries = table(stts, 8, |entry| {
+        let count = u32::from_be_bytes(be_bytes(entry, 0)?);
+        let delta = u32::from_be_bytes(be_bytes(entry, 4)?);
+        Ok((count, delta))
+    })?;
+    let mut starts = Vec::new();
+    let mut start = 0;
+    for (count, delta) in entries {
+        for _ in 0..count {
+            if starts.len() >= MAX_CHAPTERS {
+                return Ok(starts);
+            }
+            starts.push(start);
+            start += u64::from(delta);
+        }
+    }
+    Ok(starts)
+}
+
+fn sample_sizes(stsz: &[u8]) -> io::Result<Vec<u32>> {
+    let sample_size = u32::from_be_bytes(be_bytes(stsz, 4)?);
+    let count = u32::from_be_bytes(be_bytes(stsz, 8)?);
+    if sample_size != 0 {
+        let count = usize::try_from(count)
+            .unwrap_or(usize::MAX)
+            .min(MAX_CHAPTERS);
+        return Ok(vec![sample_size; count]);
+    }
+    table(&stsz[4..], 4, |entry| {
+        Ok(u32::from_be_bytes(be_bytes(entry, 0)?))
+    })
+}
+
+/// Parse entries of the table box: version, flags and the entry count
+/// followed by entries of the same size
+fn table<T>(
+    data: &[u8],
+    entry_len: usize,
+    parse: impl Fn(&[u8]) -> io::Result<T>,
+) -> io::Result<Vec<T>> {
+    let count = u32::from_be_bytes(be_bytes(data, 4)?);
+    data.get(8..)
+        .unwrap_or_default()
+        .chunks_exact(entry_len)
+        .take(usize::try_from(count).unwrap_or(usize::MAX))
+        .map(parse)
+        .collect()
+}
+
+/// Time scale and duration from `mvhd` or `mdhd` box
+fn time_header(data: &[u8]) -> io::Result<(u32, u64)> {
+    if data.first() == Some(&1) {
+        let timescale = u32::from_be_bytes(be_bytes(data, 20)?);
+        Ok((timescale, u64::from_be_bytes(be_bytes(data, 24)?)))
+    } else {
+        let timescale = u32::from_be_bytes(be_bytes(data, 12)?);
+        Ok((timescale, u32::from_be_bytes(be_bytes(data, 16)?).into()))
+    }
+}
+
+fn find(boxes: &[Mp4Box], name: [u8; 4]) -> Option<&Mp4Box> {
+    boxes.iter().find(|b| b.name == name)
+}
+
+/// Find the box by names of its ancestors starting from `boxes`
+fn find_path<R: Read + Seek>(
+    reader: &mut R,
+    boxes: &[Mp4Box],
+    path: &[[u8; 4]],
+) -> io::Result<Option<Mp4Box>> {
+    let Some((first, rest)) = path.split_first() else {
+        return Ok(None);
+    };
+    let Some(found) = find(boxes, *first).copied() else {
+        return Ok(None);
+    };
+    if rest.is_empty() {
+        return Ok(Some(found));
+    }
+    let children = children(reader, found.start, found.end)?;
+    find_path(reader, &children, rest)
+}
+
+fn read_box(reader: &mut (impl Read + Seek), mp4_box: &Mp4Box) -> io::Result<Vec<u8>> {
+    let len = mp4_box.end - mp4_box.start;
+    if len > MAX_BOX_LEN {
+        return Err(invalid_data("box is too big"));
+    }
+    reader.seek(SeekFrom::Start(mp4_box.start))?;
+    read_vec(reader, len)
+}
+
+/// List boxes between `start` and `end`
+fn children(reader: &mut (impl Read + Seek), start: u64, end: u64) -> io::Result<Vec<Mp4Box>> {
+    let mut boxes = Vec::new();
+    let mut position = start;
+    while position
+        .checked_add(8)
+        .is_some_and(|header_end| header_end <= end)
+    {
+        reader.seek(SeekFrom::Start(position))?;
+        let header = read_array::<8>(reader)?;
+        let mut size = u64::from(u32::from_be_bytes(be_bytes(&header, 0)?));
+        let mut header_len = 8;
+        if size == 1 {
+            size = u64::from_be_bytes(read_array(reader)?);
+            header_len = 16;
+        } else if size == 0 {
+            size = end - position;
+        }
+        let box_end = position
+            .checked_add(size)
+            .filter(|&box_end| size >= header_len && box_end <= end)
+            .ok_or_else(|| invalid_data("invalid box size"))?;
+        boxes.push(Mp4Box {
+            name: be_bytes(&header, 4)?,
+            start: position + header_len,
+            end: box_end,
+        });
+        position = box_end;
+    }
+    Ok(boxes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn mp4_box(name: [u8; 4], content: &[u8]) -> Vec<u8> {
+        let size = u32::try_from(content.len() + 8).unwrap();
+        [&size.to_be_bytes(), &name, content].concat()
+    }
+
+    /// Version, flags and the entry count followed by the entries
+    fn table_box(name: [u8; 4], entries: &[&[u32]]) -> Vec<u8> {
+        let count = u32::try_from(entries.len()).unwrap();
+        let mut content = [0_u32.to_be_bytes(), count.to_be_bytes()].concat();
+        for value in entries.iter().flat_map(|entry| entry.iter()) {
+            content.extend(value.to_be_bytes());
+        }
+        mp4_box(name, &content)
+    }
+
+    #[test]
+    fn text_track_samples_follow_chunk_runs() {
+        // The first chunk holds two samples, the second one
+        let mut data = b"\0\x01A\0\x01B".to_vec();
+        data.resize(100, 0);
+        data.extend(b"\0\x01C");
+        let trak_start = data.len() as u64;
+
+        let mut mdhd = vec![0; 12];
+        mdhd.extend(1000_u32.to_be_bytes());
+        mdhd.extend(3000_u32.to_be_bytes());
+        let stsz = [0_u32, 0, 3, 3, 3, 3]
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect::<Vec<_>>();
+        let stbl = [
+            table_box(*b"stts", &[&[3, 1000]]),
+            mp4_box(*b"stsz", &stsz),
+            table_box(*b"stco", &[&[0], &[100]]),
+            table_box(*b"stsc", &[&[1, 2, 1], &[2, 1, 1]]),
+        ]
+        .concat();
+        let minf = mp4_box(*b"minf", &mp4_box(*b"stbl", &stbl));
+        let mdia = mp4_box(*b"mdia", &[mp4_box(*b"mdhd", &mdhd), minf].concat());
+        data.extend(&mdia);
+
+        let mut reader = Cursor::new(data);
+        let end = reader.get_ref().len() as u64;
+        let trak = children(&mut reader, trak_start, end).unwrap();
+        let chapters = text_track(&mut reader, &trak).unwrap();
+        let titles: Vec<_> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["A", "B", "C"]);
+        assert_eq!(chapters[2].start, std::time::Duration::from_secs(2));
+    }
+
+    #[test]
+    fn huge_box_size_is_an_error() {
+        let mut data = Vec::new();
+        data.extend(16_u32.to_be_bytes());
+        data.extend(b"ftypisom\0\0\0\0");
+        data.extend(1_u32.to_be_bytes());
+        data.extend(b"moov");
+        data.extend((u64::MAX - 15).to_be_bytes());
+        data.extend([0; 64]);
+        assert!(probe(&mut Cursor::new(data)).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use etcetera::BaseStrategy;
+use fs_err as fs;
+use url::Url;
+
+use crate::{EpisodeList, ErrorKind, MediaHandler, Result};
+
+/// File opened in any application
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentFile {
+    /// Opened file
+    pub path: PathBuf,
+    /// When the file was opened last time
+    pub opened: DateTime<Local>,
+}
+
+/// Episode of the current chapter opened after the media was changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentEpisode {
+    /// Number of the episode in the chapter
+    pub episode: u8,
+    /// Name of the episode file
+    pub name: String,
+    /// When the episode was opened last time
+    pub opened: DateTime<Local>,
+}
+
+/// The freedesktop list of recently used files
+#[must_use]
+pub fn recently_used_file() -> Option<PathBuf> {
+    let dirs = etcetera::choose_base_strategy().ok()?;
+    let path = dirs.data_dir().join("recently-used.xbel");
+    path.is_file().then_some(path)
+}
+
+/// Read local files from the XBEL list of recently used files
+///
+/// # Errors
+///
+/// Fails if the file can't be read or isn't a valid XML.
+pub async fn read_recently_used(path: impl AsRef<Path>) -> Result<Vec<RecentFile>> {
+    let path = path.as_ref();
+    let content = fs::tokio::read_to_string(path).await?;
+    parse_xbel(&content).map_err(|err| ErrorKind::parse_recently_used(path, err.to_string()))
+}
+
+/// `href` of every `bookmark` along with the latest of its dates
+fn parse_xbel(content: &str) -> std::result::Result<Vec<RecentFile>, roxmltree::Error> {
+    let document = roxmltree::Document::parse(content)?;
+    let files = document
+        .descendants()
+        .filter(|node| node.has_tag_name("bookmark"))
+        .filter_map(|bookmark| {
+            let path = Url::parse(bookmark.attribute("href")?)
+                .ok()?
+                .to_file_path()
+                .ok()?;
+            let opened = ["added", "modified", "visited"]
+                .into_iter()
+                .filter_map(|name| bookmark.attribute(name))
+                .filter_map(|date| DateTime::parse_from_rfc3339(date).ok())
+                .max()?;
+            let opened = opened.with_timezone(&Local);
+            Some(RecentFile { path, opened })
+        })
+        .collect();
+    Ok(files)
+}
+
+/// Find the latest opened one of the `episodes` of the current chapter if
+/// it follows the current episode. Files opened before the last change of
+/// the media are ignored
+#[must_use]
+pub fn find_recent_episode(
+    media: &MediaHandler,
+    episodes: &EpisodeList,
+    recent: &[RecentFile],
+) -> Option<RecentEpisode> {
+    let mut opened: Vec<_> = recent
+        .iter()
+        .filter(|file| file.opened > media.changing_date())
+        .filter(|file| media.may_contain(&file.path))
+        .collect();
+    opened.sort_by_key(|file| Reverse(file.opened));
+    let latest = opened.into_iter().find_map(|file| {
+        let (episode, found) = episodes.find_playing(&file.path, Duration::ZERO)?;
+        Some(RecentEpisode {
+            episode,
+            name: found.name().into_owned(),
+            opened: file.opened,
+        })
+    });
+    latest.filter(|latest| latest.episode > media.episode())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XBEL: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xbel version="1.0"
+      xmlns:bookmark="http://www.freedesktop.org/standards/desktop-bookmarks"
+      xmlns:mime="http://www.freedesktop.org/standards/shared-mime-info">
+  <bookmark href="file:///videos/show/01%20Pilot.mkv" added="2026-01-02T10:00:00Z" modified="2026-01-03T10:00:00Z" visited="2026-01-04T10:00:00.123456Z">
+    <info>
+      <metadata owner="http://freedesktop.org">
+        <mime:mime-type type="video/x-matroska"/>
+      </metadata>
+    </info>
+  </bookmark>
+  <bookmark href="https://example.com/remote.mkv" added="2026-01-02T10:00:00Z"/>
+  <bookmark href="file:///videos/show/02.mkv"/>
+</xbel>
+"#;
+
+    #[test]
+    fn parses_local_files_with_latest_date() {
+        let files = parse_xbel(XBEL).unwrap();
+        let opened = DateTime::parse_from_rfc3339("2026-01-04T10:00:00.123456Z").unwrap();
+        assert_eq!(
+            files,
+            [RecentFile {
+                path: PathBuf::from("/videos/show/01 Pilot.mkv"),
+                opened: opened.with_timezone(&Local),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn latest_opened_episode_of_chapter_is_found() {
+        let dir = std::env::temp_dir().join(format!("omt-recent-{}", std::process::id()));
+        let chapter = dir.join("show");
+        fs::create_dir_all(&chapter).unwrap();
+        for name in ["01.mkv", "02.mkv", "03.mkv"] {
+            fs::write(chapter.join(name), []).unwrap();
+        }
+        let config = std::sync::Arc::new(crate::Config {
+            data_dir: dir.clone(),
+            config_dir: dir.clone(),
+            archive_cache: dir.clone(),
+            thumbnails: dir.clone(),
+        });
+        let mut media = MediaHandler::new("Show", config).unwrap();
+        let recent = |path: PathBuf, hours| RecentFile {
+            path,
+            opened: Local::now() + chrono::Duration::hours(hours),
+        };
+        let recent = [
+            recent(chapter.join("02.mkv"), 1),
+            recent(chapter.join("03.mkv"), 2),
+            recent(dir.join("other/04.mkv"), 3),
+        ];
+        // Media without a chapter path has no episodes
+        let empty = EpisodeList::default();
+        assert_eq!(find_recent_episode(&media, &empty, &recent), None);
+
+        media.set_chapter_path(&chapter).unwrap();
+        let episodes = media
+            .episode_list(&crate::Settings::default())
+            .await
+            .unwrap();
+        let found = find_recent_episode(&media, &episodes, &recent).unwrap();
+        assert_eq!((found.episode, found.name.as_str()), (3, "03"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,28 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use serde::{Deserialize, Serialize};
+
+/// Local HTTP server which lets scripts read and change media
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RemoteApi {
+    /// Start the server along with the application
+    pub enabled: bool,
+    /// Address and port to listen on
+    pub address: String,
+    /// Requests without `Authorization: Bearer <token>` header are rejected
+    pub token: String,
+}
+
+impl Default for RemoteApi {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: String::from("127.0.0.1:8645"),
+            token: String::new(),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::PathBuf;
+
+use crate::{MediaExtensions, SortOrder};
+
+/// How episodes are looked up in a directory
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Order of episodes and chapters
+    pub sort_order: SortOrder,
+    /// Which files are treated as media
+    pub media_extensions: MediaExtensions,
+    /// Root of the series whose `.omtignore` is applied to the chapters
+    pub series_path: Option<PathBuf>,
+}
@@ -0,0 +1,86 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::io::Write;
+
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ArchiveLimits, ChapterFilter, Config, ErrorKind, MediaExtensions, Player, RemoteApi, Result,
+};
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// Settings shared by all media
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settings {
+    /// Patterns for chapter directories applied to every media
+    #[serde(default = "ChapterFilter::with_default_exclude")]
+    pub chapter_filter: ChapterFilter,
+    /// Which files are treated as media
+    #[serde(default)]
+    pub media_extensions: MediaExtensions,
+    /// Program used to watch episodes
+    #[serde(default)]
+    pub player: Player,
+    /// Limits of the directory where episodes are extracted from archives
+    #[serde(default)]
+    pub archive_limits: ArchiveLimits,
+    /// Local HTTP server
+    #[serde(default)]
+    pub remote_api: RemoteApi,
+    /// Update media from files played in any MPRIS player
+    #[serde(default)]
+    pub track_players: bool,
+    /// Show media as a grid of covers in the GUI
+    #[serde(default)]
+    pub poster_grid: bool,
+}
+
+impl Settings {
+    /// Read settings, defaults are used when there is no file
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or parsed.
+    pub fn read(config: &Config) -> Result<Self> {
+        let path = config.config_dir.join(SETTINGS_FILE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let file_content = fs::read_to_string(&path)?;
+        serde_json::from_str(&file_content).map_err(|source| ErrorKind::deserialize(path, source))
+    }
+
+    /// Write settings to the config directory
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be written.
+    pub fn save(&self, config: &Config) -> Result<()> {
+        let path = config.config_dir.join(SETTINGS_FILE_NAME);
+        let mut file = fs::File::create(path)?;
+        serde_json::to_writer_pretty(&file, &self)
+            .map_err(|source| ErrorKind::serialize(source, SETTINGS_FILE_NAME))?;
+        file.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            chapter_filter: ChapterFilter::with_default_exclude(),
+            media_extensions: MediaExtensions::default(),
+            player: Player::default(),
+            archive_limits: ArchiveLimits::default(),
+            remote_api: RemoteApi::default(),
+            track_players: false,
+            poster_grid: false,
+        }
+    }
+}
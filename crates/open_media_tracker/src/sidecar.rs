@@ -0,0 +1,81 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use mime_guess::mime;
+
+const SUBTITLE_EXTENSIONS: &[&str] = &["srt", "ass", "ssa", "vtt", "sub", "idx", "sup", "smi"];
+
+/// What the sidecar file adds to the episode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SidecarKind {
+    /// Subtitle file
+    Subtitle,
+    /// External audio track
+    Audio,
+}
+
+/// File which is played together with the episode
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Sidecar {
+    path: PathBuf,
+    kind: SidecarKind,
+}
+
+impl Sidecar {
+    /// Sidecar of the given kind
+    pub fn new(path: impl Into<PathBuf>, kind: SidecarKind) -> Self {
+        let path = path.into();
+        Self { path, kind }
+    }
+
+    /// Path of the file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Name of the file
+    #[must_use]
+    pub fn file_name(&self) -> Cow<'_, str> {
+        self.path.file_name().unwrap_or_default().to_string_lossy()
+    }
+
+    /// What the file adds to the episode
+    #[must_use]
+    pub const fn kind(&self) -> SidecarKind {
+        self.kind
+    }
+}
+
+pub fn is_subtitle(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| {
+        SUBTITLE_EXTENSIONS
+            .iter()
+            .any(|subtitle| extension.eq_ignore_ascii_case(subtitle))
+    })
+}
+
+pub fn is_audio(path: &Path) -> bool {
+    mime_guess::from_path(path)
+        .first()
+        .is_some_and(|mime| mime.type_() == mime::AUDIO)
+}
+
+/// Whether the file is named after the episode: `Episode 01.srt` or
+/// `Episode 01.en.forced.srt` belong to `Episode 01.mkv`
+pub fn belongs_to(file: &Path, episode: &Path) -> bool {
+    let (Some(file_stem), Some(episode_stem)) = (file.file_stem(), episode.file_stem()) else {
+        return false;
+    };
+    let file_stem = file_stem.to_string_lossy();
+    let episode_stem = episode_stem.to_string_lossy();
+    file_stem
+        .strip_prefix(&*episode_stem)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
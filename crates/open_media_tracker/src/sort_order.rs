@@ -0,0 +1,49 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::cmp::Ordering;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::natural_cmp;
+
+/// Order of episode files and chapter directories
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortOrder {
+    /// "Episode 2" goes before "Episode 10"
+    #[default]
+    Natural,
+    /// Strict comparison of file names, "Episode 10" goes before "Episode 2"
+    Lexical,
+}
+
+impl SortOrder {
+    /// Compare paths of episodes or chapters
+    #[must_use]
+    pub fn compare(self, a: &Path, b: &Path) -> Ordering {
+        match self {
+            Self::Natural => natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()),
+            Self::Lexical => a.cmp(b),
+        }
+    }
+
+    /// Whether numbers in names are compared by value
+    #[must_use]
+    pub const fn is_natural(self) -> bool {
+        matches!(self, Self::Natural)
+    }
+}
+
+impl From<bool> for SortOrder {
+    fn from(natural: bool) -> Self {
+        if natural {
+            Self::Natural
+        } else {
+            Self::Lexical
+        }
+    }
+}
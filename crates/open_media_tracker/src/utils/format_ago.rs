@@ -0,0 +1,23 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use chrono::{DateTime, Local};
+
+/// Format the time passed since the date as `today`, `yesterday`,
+/// `3 days ago`, `2 weeks ago`, `5 months ago` or `2 years ago`
+#[must_use]
+pub fn format_ago(date: DateTime<Local>) -> String {
+    let days = (Local::now().date_naive() - date.date_naive()).num_days();
+    let (count, unit) = match days {
+        ..=0 => return String::from("today"),
+        1 => return String::from("yesterday"),
+        2..14 => (days, "day"),
+        14..60 => (days / 7, "week"),
+        60..730 => (days / 30, "month"),
+        _ => (days / 365, "year"),
+    };
+    format!("{count} {unit}s ago")
+}
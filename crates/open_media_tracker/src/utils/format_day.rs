@@ -0,0 +1,19 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use chrono::{DateTime, Local};
+
+/// Format the day relative to today as `today`, `yesterday` or
+/// `on 2024-05-17`
+#[must_use]
+pub fn format_day(date: DateTime<Local>) -> String {
+    let days = (Local::now().date_naive() - date.date_naive()).num_days();
+    match days {
+        0 => String::from("today"),
+        1 => String::from("yesterday"),
+        _ => date.format("on %Y-%m-%d").to_string(),
+    }
+}
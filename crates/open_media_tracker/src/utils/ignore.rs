@@ -0,0 +1,88 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+pub const IGNORE_FILE_NAME: &str = ".omtignore";
+
+/// Extensions of the files which are still downloading
+const PARTIAL_DOWNLOAD_EXTENSIONS: &[&str] = &["part", "crdownload", "partial", "download", "!qb"];
+
+/// Rules from `.omtignore` files (gitignore syntax). Hidden files and
+/// partial downloads are ignored unless whitelisted with `!pattern`.
+pub struct IgnoreRules {
+    gitignores: Vec<Gitignore>,
+}
+
+impl IgnoreRules {
+    /// Read `.omtignore` files of the directories from `root` down to `dir`.
+    /// If `dir` is outside of `root` only its own file is read
+    pub fn read_tree(root: &Path, dir: &Path) -> Self {
+        let mut dirs: Vec<_> = dir
+            .ancestors()
+            .take_while(|ancestor| ancestor.starts_with(root))
+            .collect();
+        if dirs.is_empty() {
+            dirs.push(dir);
+        }
+        dirs.reverse();
+        Self::read(dirs)
+    }
+
+    /// Read `.omtignore` files from the given directories, the outermost
+    /// first. Rules of a deeper directory win like in gitignore
+    pub fn read<'a>(dirs: impl IntoIterator<Item = &'a Path>) -> Self {
+        let gitignores = dirs
+            .into_iter()
+            .filter_map(|dir| {
+                let ignore_file = dir.join(IGNORE_FILE_NAME);
+                if !ignore_file.is_file() {
+                    return None;
+                }
+                let mut builder = GitignoreBuilder::new(dir);
+                if let Some(err) = builder.add(&ignore_file) {
+                    log::warn!("{}: {err}", ignore_file.display());
+                }
+                builder
+                    .build()
+                    .inspect_err(|err| log::warn!("{}: {err}", ignore_file.display()))
+                    .ok()
+            })
+            .collect();
+        Self { gitignores }
+    }
+
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let matched = self
+            .gitignores
+            .iter()
+            .rev()
+            .filter(|gitignore| path.starts_with(gitignore.path()))
+            .map(|gitignore| gitignore.matched_path_or_any_parents(path, is_dir))
+            .find(|matched| !matched.is_none());
+        match matched {
+            Some(Match::Ignore(_)) => true,
+            Some(Match::Whitelist(_)) => false,
+            Some(Match::None) | None => is_hidden(path) || is_partial_download(path),
+        }
+    }
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+fn is_partial_download(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| {
+        PARTIAL_DOWNLOAD_EXTENSIONS
+            .iter()
+            .any(|partial| extension.eq_ignore_ascii_case(partial))
+    })
+}
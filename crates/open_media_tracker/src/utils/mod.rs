@@ -0,0 +1,25 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Helpers shared by the frontends
+
+mod format_ago;
+mod format_day;
+mod format_duration;
+mod ignore;
+mod natural_cmp;
+mod open;
+mod read_dir;
+mod sibling_dir;
+
+pub use format_ago::format_ago;
+pub use format_day::format_day;
+pub use format_duration::format_duration;
+pub(crate) use ignore::IgnoreRules;
+pub use natural_cmp::natural_cmp;
+pub use open::{OpenError, open};
+pub(crate) use read_dir::{read_dir, read_dir_in_tree, read_dir_with_filter};
+pub(crate) use sibling_dir::{next_dir, prev_dir};
@@ -0,0 +1,96 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Compare strings treating runs of digits as numbers, so "Episode 2" goes
+/// before "Episode 10". Letters are compared case-insensitively, the strict
+/// comparison is used only to break ties.
+#[must_use]
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    while let (Some(&a_char), Some(&b_char)) = (a_chars.peek(), b_chars.peek()) {
+        let ordering = if a_char.is_ascii_digit() && b_char.is_ascii_digit() {
+            let a_number = take_number(&mut a_chars);
+            let b_number = take_number(&mut b_chars);
+            compare_numbers(&a_number, &b_number)
+        } else {
+            a_chars.next();
+            b_chars.next();
+            a_char.to_lowercase().cmp(b_char.to_lowercase())
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a_chars
+        .next()
+        .is_some()
+        .cmp(&b_chars.next().is_some())
+        .then_with(|| a.cmp(b))
+}
+
+fn take_number(chars: &mut Peekable<Chars>) -> String {
+    let mut number = String::new();
+    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+        number.push(digit);
+    }
+    number
+}
+
+fn compare_numbers(a: &str, b: &str) -> Ordering {
+    let a_trimmed = a.trim_start_matches('0');
+    let b_trimmed = b.trim_start_matches('0');
+    a_trimmed
+        .len()
+        .cmp(&b_trimmed.len())
+        .then_with(|| a_trimmed.cmp(b_trimmed))
+        .then_with(|| b.len().cmp(&a.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_compare_by_value() {
+        assert_eq!(natural_cmp("ep2", "ep10"), Ordering::Less);
+        assert_eq!(natural_cmp("ep10", "ep2"), Ordering::Greater);
+        assert_eq!(
+            natural_cmp("Episode 9.mkv", "Episode 10.mkv"),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn leading_zeros_are_ignored() {
+        assert_eq!(natural_cmp("ep010", "ep9"), Ordering::Greater);
+        assert_eq!(natural_cmp("ep02", "ep3"), Ordering::Less);
+        assert_ne!(natural_cmp("ep02", "ep2"), Ordering::Equal);
+        assert_eq!(natural_cmp("ep02", "ep02"), Ordering::Equal);
+    }
+
+    #[test]
+    fn letters_ignore_case() {
+        assert_eq!(natural_cmp("Ep2", "ep10"), Ordering::Less);
+        assert_eq!(natural_cmp("a", "B"), Ordering::Less);
+        assert_eq!(natural_cmp("B", "a"), Ordering::Greater);
+        assert_ne!(natural_cmp("Ep1", "ep1"), Ordering::Equal);
+    }
+
+    #[test]
+    fn digits_and_letters_boundaries() {
+        assert_eq!(natural_cmp("ep1a", "ep1b"), Ordering::Less);
+        assert_eq!(natural_cmp("ep1", "ep1a"), Ordering::Less);
+        assert_eq!(natural_cmp("ep1b", "ep2a"), Ordering::Less);
+        assert_eq!(natural_cmp("1", "a"), Ordering::Less);
+        assert_eq!(natural_cmp("ep", "ep1"), Ordering::Less);
+        assert_eq!(natural_cmp("2.5", "2.10"), Ordering::Less);
+    }
+}
@@ -8,11 +8,17 @@ use std::ffi::OsStr;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+/// Open the path with the default program
+///
+/// # Errors
+///
+/// Fails if no program could be started.
 pub fn open(path: impl AsRef<OsStr>) -> Result<(), OpenError> {
     let path = path.as_ref();
     open::that_detached(path).map_err(|source| OpenError::new(source, path))
 }
 
+/// Failed to open the file with the default program of the system
 #[derive(Debug, Clone, thiserror::Error)]
 #[error("{path}: Failed to open default program: {source}")]
 pub struct OpenError {
@@ -0,0 +1,84 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::{Path, PathBuf};
+
+use expand_tilde::ExpandTilde;
+use fs_err as fs;
+
+use crate::Result;
+use crate::utils::IgnoreRules;
+
+/// Read directory skipping entries ignored by its `.omtignore` file,
+/// hidden files and partial downloads
+pub async fn read_dir_with_filter<P>(path: P, filter: fn(&Path) -> bool) -> Result<Vec<PathBuf>>
+where
+    P: AsRef<Path>,
+{
+    read_dir_in_tree(path, None, filter).await
+}
+
+/// Read all entries of the directory
+pub async fn read_dir(path: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    let mut read_dir = fs::tokio::read_dir(path.as_ref()).await?;
+    let mut paths = Vec::new();
+    while let Some(entry) = read_dir.next_entry().await? {
+        paths.push(entry.path());
+    }
+    Ok(paths)
+}
+
+/// Same as [`read_dir_with_filter`], but `.omtignore` files of the
+/// directories from `root` down to `path` are honoured too
+pub async fn read_dir_in_tree<P>(
+    path: P,
+    root: Option<&Path>,
+    filter: fn(&Path) -> bool,
+) -> Result<Vec<PathBuf>>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref().expand_tilde()?;
+    let ignore_rules = match root {
+        Some(root) => IgnoreRules::read_tree(&root.expand_tilde()?, &path),
+        None => IgnoreRules::read([&*path]),
+    };
+    let mut read_dir = fs::tokio::read_dir(&*path).await?;
+    let mut paths = Vec::new();
+    while let Some(entry) = read_dir.next_entry().await? {
+        let path = entry.path();
+        let is_dir = entry.file_type().await?.is_dir();
+        if filter(&path) && !ignore_rules.is_ignored(&path, is_dir) {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn deeper_ignore_file_wins() {
+        let root = std::env::temp_dir().join(format!("omt-ignore-{}", std::process::id()));
+        let chapter = root.join("Season 1");
+        fs::create_dir_all(&chapter).unwrap();
+        fs::write(root.join(".omtignore"), "*.mkv\n").unwrap();
+        fs::write(chapter.join(".omtignore"), "!keep.mkv\n").unwrap();
+        for file_name in ["keep.mkv", "skip.mkv", "other.mp4"] {
+            fs::write(chapter.join(file_name), "").unwrap();
+        }
+
+        let mut paths = read_dir_in_tree(&chapter, Some(&root), Path::is_file)
+            .await
+            .unwrap();
+        paths.sort();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(paths, [chapter.join("keep.mkv"), chapter.join("other.mp4")]);
+    }
+}
@@ -0,0 +1,83 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::{Path, PathBuf};
+
+use expand_tilde::ExpandTilde;
+
+use crate::utils;
+use crate::{
+    ChapterMatcher, ErrorKind, Result, ScanOptions, is_archive, is_playlist, read_episodes,
+};
+
+pub async fn next_dir(
+    path: impl AsRef<Path>,
+    options: &ScanOptions,
+    matcher: &ChapterMatcher,
+) -> Result<PathBuf> {
+    let path = path.as_ref();
+    sibling_dir(path, options, matcher, |index| index.checked_add(1))
+        .await?
+        .ok_or_else(|| ErrorKind::find_next_chapter(path))
+}
+
+pub async fn prev_dir(
+    path: impl AsRef<Path>,
+    options: &ScanOptions,
+    matcher: &ChapterMatcher,
+) -> Result<PathBuf> {
+    let path = path.as_ref();
+    sibling_dir(path, options, matcher, |index| index.checked_sub(1))
+        .await?
+        .ok_or_else(|| ErrorKind::find_prev_chapter(path))
+}
+
+/// Find directory next to the `path` in the parent directory. `step` gets
+/// index of a directory and returns index of the next one to check.
+/// Directories not matched by `matcher` and directories without episodes
+/// are skipped. If `path` is a playlist or an archive, sibling playlists
+/// or archives are searched.
+async fn sibling_dir(
+    path: &Path,
+    options: &ScanOptions,
+    matcher: &ChapterMatcher,
+    step: fn(usize) -> Option<usize>,
+) -> Result<Option<PathBuf>> {
+    let path = path.expand_tilde()?;
+
+    let parent = path
+        .parent()
+        .ok_or_else(|| ErrorKind::find_parent(&*path))?;
+    let filter = if is_playlist(&path) {
+        is_playlist
+    } else if is_archive(&path) {
+        is_archive
+    } else {
+        Path::is_dir
+    };
+    let mut paths = utils::read_dir_with_filter(parent, filter).await?;
+    let dir_name = path.file_name().unwrap_or_default();
+    paths.sort_by(|a, b| options.sort_order.compare(a, b));
+    let Some(mut index) = paths
+        .iter()
+        .position(|path| path.file_name() == Some(dir_name))
+    else {
+        return Ok(None);
+    };
+    while let Some(sibling_index) = step(index).filter(|&index| index < paths.len()) {
+        index = sibling_index;
+        let sibling_dir = &paths[index];
+        let sibling_name = sibling_dir.file_name().unwrap_or_default();
+        if !matcher.is_match(&sibling_name.to_string_lossy()) {
+            continue;
+        }
+        let episodes = read_episodes(sibling_dir, options).await;
+        if episodes.is_ok_and(|episodes| !episodes.is_empty()) {
+            return Ok(Some(paths.swap_remove(index)));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,104 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use etcetera::BaseStrategy;
+use fs_err as fs;
+
+use crate::{EpisodeList, MediaHandler, Result};
+
+/// Where mpv stopped playing an episode of the current chapter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumePoint {
+    /// Number of the episode in the chapter
+    pub episode: u8,
+    /// Position from the beginning of the episode
+    pub position: Duration,
+    /// When mpv saved the position
+    pub saved: SystemTime,
+}
+
+/// Directory of mpv resume files written with `--save-position-on-quit`.
+/// mpv before 0.36 kept it in the config directory
+#[must_use]
+pub fn watch_later_dir() -> Option<PathBuf> {
+    let dirs = etcetera::choose_base_strategy().ok()?;
+    let state_dir = dirs
+        .state_dir()
+        .map(|dir| dir.join("mpv").join("watch_later"));
+    let config_dir = dirs.config_dir().join("mpv").join("watch_later");
+    state_dir
+        .into_iter()
+        .chain([config_dir])
+        .find(|dir| dir.is_dir())
+}
+
+/// Find the latest mpv resume file among `episodes` of the current chapter.
+/// Files saved before the last change of the media and files which point to
+/// the current episode and position are ignored
+///
+/// # Errors
+///
+/// Fails if a resume file can't be read.
+pub async fn find_resume_point(
+    media: &MediaHandler,
+    episodes: &EpisodeList,
+    dir: &Path,
+) -> Result<Option<ResumePoint>> {
+    let changed = SystemTime::from(media.changing_date());
+    let mut latest: Option<(SystemTime, &Path, Duration)> = None;
+    let mut checked = HashSet::new();
+    for episode in episodes.iter() {
+        let path = episode.path();
+        if episode.archive_entry().is_some() || !checked.insert(path) {
+            continue;
+        }
+        let resume_file = dir.join(resume_file_name(path));
+        let Ok(metadata) = fs::tokio::metadata(&resume_file).await else {
+            continue;
+        };
+        let saved = metadata.modified()?;
+        if saved <= changed || latest.is_some_and(|(latest, ..)| latest >= saved) {
+            continue;
+        }
+        if let Some(start) = read_start(&resume_file).await? {
+            latest = Some((saved, path, start));
+        }
+    }
+    let Some((saved, path, start)) = latest else {
+        return Ok(None);
+    };
+    let Some((number, episode)) = episodes.find_playing(path, start) else {
+        return Ok(None);
+    };
+    let position = start.saturating_sub(episode.start().unwrap_or_default());
+    if number == media.episode() && media.position() == Some(position) {
+        return Ok(None);
+    }
+    Ok(Some(ResumePoint {
+        episode: number,
+        position,
+        saved,
+    }))
+}
+
+/// mpv names resume files by MD5 of the played path
+fn resume_file_name(path: &Path) -> String {
+    format!("{:X}", md5::compute(path.as_os_str().as_encoded_bytes()))
+}
+
+async fn read_start(path: &Path) -> Result<Option<Duration>> {
+    let content = fs::tokio::read_to_string(path).await?;
+    let start = content
+        .lines()
+        .find_map(|line| line.strip_prefix("start="))
+        .and_then(|secs| secs.trim().parse().ok())
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+    Ok(start)
+}
@@ -0,0 +1,147 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Changes of media made without confirmations, shared by the command-line
+//! interface and the remote API
+
+use std::path::PathBuf;
+
+use open_media_tracker::{
+    Config, EpisodeList, ErrorKind, MediaHandler, PlayWarning, Reconciliation, Result, Settings,
+    extract,
+};
+use serde::Deserialize;
+
+use crate::report::MediaReport;
+
+/// Fields of the media to change, missing ones are kept
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Changes {
+    pub chapter: Option<u8>,
+    pub episode: Option<u8>,
+    /// Directory, playlist or archive with episodes of the chapter
+    pub chapter_path: Option<PathBuf>,
+    /// Directory with chapters as subdirectories
+    pub series_path: Option<PathBuf>,
+}
+
+/// Episodes of the current chapter. The episode number is corrected if the
+/// remembered episode file has moved
+pub async fn episodes(media: &mut MediaHandler, settings: &Settings) -> Result<EpisodeList> {
+    let episodes = media.episode_list(settings).await?;
+    match media.reconcile_episode(&episodes)? {
+        Reconciliation::Unchanged | Reconciliation::Moved { .. } => {}
+        Reconciliation::Missing { file_name } => {
+            log::warn!("{file_name}: Episode file is missing");
+            return Ok(episodes);
+        }
+    }
+    if let Some(episode) = episodes.find(media.episode()) {
+        media.set_episode_anchor(episode)?;
+    }
+    Ok(episodes)
+}
+
+/// Move to the next episode, the next chapter follows the last episode
+pub async fn next(media: &mut MediaHandler, settings: &Settings) -> Result<()> {
+    let last_number = episodes(media, settings).await?.last_number();
+    let next_episode = media.episode().saturating_add(1);
+    if last_number.is_some_and(|last| usize::from(next_episode) <= last) {
+        media.set_episode(next_episode)?;
+    } else {
+        let next_chapter_path = media.next_chapter_path(settings).await?;
+        media.set_chapter_path(next_chapter_path)?;
+        media.reload_metadata().await?;
+        media.set_chapter(media.chapter().saturating_add(1))?;
+        let first_number = media.episode_list(settings).await?.first_number();
+        media.set_episode(first_number)?;
+    }
+    episodes(media, settings).await?;
+    Ok(())
+}
+
+/// Apply the changes. The chapter number switches the chapter path to the
+/// chapter of the series unless the path is given
+pub async fn change(media: &mut MediaHandler, settings: &Settings, changes: Changes) -> Result<()> {
+    let new_paths = changes.series_path.is_some() || changes.chapter_path.is_some();
+    if let Some(series_path) = changes.series_path {
+        media.set_series_path(Some(series_path))?;
+    }
+    if let Some(chapter_path) = changes.chapter_path {
+        media.set_chapter_path(chapter_path)?;
+    } else if let Some(chapter) = changes.chapter {
+        switch_chapter(media, settings, chapter).await?;
+    }
+    if new_paths {
+        media.reload_metadata().await?;
+    }
+    if let Some(chapter) = changes.chapter {
+        media.set_chapter(chapter)?;
+    }
+    if let Some(episode) = changes.episode {
+        set_episode(media, settings, episode).await?;
+    }
+    Ok(())
+}
+
+/// Switch to the chapter of the series, the episode is reset to the first
+async fn switch_chapter(media: &mut MediaHandler, settings: &Settings, chapter: u8) -> Result<()> {
+    let chapters = media.chapter_list(settings).await?;
+    let Some(chapter) = usize::from(chapter)
+        .checked_sub(1)
+        .and_then(|index| chapters.get(index))
+    else {
+        return Ok(());
+    };
+    if chapter.path() != media.chapter_path() {
+        media.set_chapter_path(chapter.path())?;
+        media.reload_metadata().await?;
+        let first_number = media.episode_list(settings).await?.first_number();
+        media.set_episode(first_number)?;
+    }
+    Ok(())
+}
+
+async fn set_episode(media: &mut MediaHandler, settings: &Settings, episode: u8) -> Result<()> {
+    let episodes = media.episode_list(settings).await?;
+    if episodes
+        .last_number()
+        .is_some_and(|last| usize::from(episode) > last)
+    {
+        return Err(ErrorKind::EpisodeNotFound);
+    }
+    media.set_episode(episode)
+}
+
+/// Play the current episode, episodes from archives are extracted first.
+/// Returns what the system player couldn't be told about
+pub async fn watch(
+    media: &mut MediaHandler,
+    settings: &Settings,
+    config: &Config,
+) -> Result<Option<PlayWarning>> {
+    let episodes = episodes(media, settings).await?;
+    let episode = episodes
+        .find(media.episode())
+        .ok_or(ErrorKind::EpisodeNotFound)?;
+    let Some(entry) = episode.archive_entry().cloned() else {
+        return settings.player.play(episode);
+    };
+    let cache_dir = config.archive_cache.clone();
+    let limits = settings.archive_limits.clone();
+    let path = extract(entry, cache_dir, limits).await?;
+    settings.player.play(&episode.extracted(path))
+}
+
+/// Media along with its current episode, episode errors are only logged
+pub async fn report(media: &mut MediaHandler, settings: &Settings) -> MediaReport {
+    let episodes = episodes(media, settings)
+        .await
+        .inspect_err(|err| log::warn!("{err}"))
+        .ok();
+    MediaReport::with_episodes(media, episodes.as_ref())
+}
@@ -0,0 +1,122 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Local HTTP server which lets phone shortcuts and scripts read and change
+//! media. Requests are answered by the running frontend so the changes are
+//! shown right away
+//!
+//! Endpoints, all of them answer with JSON:
+//! - `GET /media` lists media
+//! - `GET /media/{name}` shows the media and its current episode
+//! - `PATCH /media/{name}` changes `chapter`, `episode`, `chapter_path` or
+//!   `series_path` given in the JSON body
+//! - `POST /media/{name}/next` moves to the next episode
+//! - `POST /media/{name}/watch` plays the current episode
+
+mod server;
+
+use std::fmt::Display;
+use std::sync::mpsc::SyncSender;
+
+use open_media_tracker::{Config, ErrorKind, MediaHandler, Result, Settings};
+use serde::Serialize;
+
+use crate::actions::{self, Changes};
+use crate::error::AppError;
+use crate::report::MediaReport;
+pub use server::spawn;
+
+/// Call of the API waiting for the response
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub call: Call,
+    reply: SyncSender<Response>,
+}
+
+impl Request {
+    pub fn reply(self, response: Response) {
+        if self.reply.send(response).is_err() {
+            log::warn!("Remote API: The server stopped before the response was sent");
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Call {
+    List,
+    Media { name: String, action: Action },
+}
+
+#[derive(Debug, Clone)]
+pub enum Action {
+    Show,
+    Change(Changes),
+    Next,
+    Watch,
+}
+
+/// JSON body along with HTTP status code
+#[derive(Debug, Clone)]
+pub struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    pub fn ok(value: &impl Serialize) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Self { status: 200, body },
+            Err(err) => Self::message(500, err),
+        }
+    }
+
+    pub fn error(error: &AppError) -> Self {
+        let status = match error {
+            AppError::MediaNotFound { .. } => 404,
+            AppError::Library(
+                ErrorKind::EpisodeNotFound
+                | ErrorKind::FindNextChapterPath { .. }
+                | ErrorKind::FindPrevChapterPath { .. },
+            ) => 409,
+            _ => 500,
+        };
+        Self::message(status, error)
+    }
+
+    fn message(status: u16, message: impl Display) -> Self {
+        let body = serde_json::json!({ "error": message.to_string() }).to_string();
+        Self { status, body }
+    }
+}
+
+impl<T: Serialize, E: Into<AppError>> From<std::result::Result<T, E>> for Response {
+    fn from(value: std::result::Result<T, E>) -> Self {
+        match value {
+            Ok(value) => Self::ok(&value),
+            Err(err) => Self::error(&err.into()),
+        }
+    }
+}
+
+/// Perform the action and report the media after it
+pub async fn perform(
+    action: Action,
+    media: &mut MediaHandler,
+    settings: &Settings,
+    config: &Config,
+) -> Result<MediaReport> {
+    match action {
+        Action::Show => {}
+        Action::Change(changes) => actions::change(media, settings, changes).await?,
+        Action::Next => actions::next(media, settings).await?,
+        Action::Watch => {
+            if let Some(warning) = actions::watch(media, settings, config).await? {
+                log::warn!("Remote API: {}: {warning}", media.name());
+            }
+        }
+    }
+    Ok(actions::report(media, settings).await)
+}
@@ -0,0 +1,175 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::borrow::Cow;
+use std::io::{self, Read};
+use std::sync::mpsc as std_mpsc;
+use std::thread;
+use std::time::Duration;
+
+use open_media_tracker::RemoteApi;
+use percent_encoding::percent_decode_str;
+use tiny_http::{Header, Method, Server};
+use tokio::sync::mpsc;
+
+use super::{Action, Call, Request, Response};
+use crate::actions::Changes;
+use crate::error::{AppError, AppResult};
+
+/// How often the server checks that requests are still awaited
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// The previous server may hold the address for a moment after restart
+const BIND_ATTEMPTS: usize = 10;
+const MAX_BODY_LEN: u64 = 64 * 1024;
+const REQUESTS_BUFFER: usize = 16;
+
+/// Start the server in the background. It stops once the receiver is dropped
+pub async fn spawn(settings: &RemoteApi) -> AppResult<mpsc::Receiver<Request>> {
+    if settings.token.is_empty() {
+        return Err(AppError::api_server(&settings.address, "Token is not set"));
+    }
+    let address = settings.address.clone();
+    let server = tokio::task::spawn_blocking(move || bind(&address))
+        .await
+        .map_err(io::Error::other)??;
+    let (sender, receiver) = mpsc::channel(REQUESTS_BUFFER);
+    let token = settings.token.clone();
+    thread::Builder::new()
+        .name(String::from("remote-api"))
+        .spawn(move || serve(&server, &token, &sender))?;
+    Ok(receiver)
+}
+
+fn bind(address: &str) -> AppResult<Server> {
+    let mut attempts = BIND_ATTEMPTS;
+    loop {
+        match Server::http(address) {
+            Ok(server) => return Ok(server),
+            Err(err) => {
+                let in_use = err
+                    .downcast_ref::<io::Error>()
+                    .is_some_and(|err| err.kind() == io::ErrorKind::AddrInUse);
+                attempts -= 1;
+                if !in_use || attempts == 0 {
+                    return Err(AppError::api_server(address, err.to_string()));
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+fn serve(server: &Server, token: &str, requests: &mpsc::Sender<Request>) {
+    while !requests.is_closed() {
+        let mut request = match server.recv_timeout(POLL_INTERVAL) {
+            Ok(Some(request)) => request,
+            Ok(None) => continue,
+            Err(err) => {
+                log::error!("Remote API: {err}");
+                return;
+            }
+        };
+        let response = answer(&mut request, token, requests);
+        let mut http_response =
+            tiny_http::Response::from_string(response.body).with_status_code(response.status);
+        if let Ok(header) = Header::from_bytes("Content-Type", "application/json") {
+            http_response.add_header(header);
+        }
+        if let Err(err) = request.respond(http_response) {
+            log::warn!("Remote API: Failed to respond: {err}");
+        }
+    }
+}
+
+fn answer(
+    request: &mut tiny_http::Request,
+    token: &str,
+    requests: &mpsc::Sender<Request>,
+) -> Response {
+    if !authorized(request, token) {
+        return Response::message(401, "Wrong or missing token");
+    }
+    let call = match route(request) {
+        Ok(call) => call,
+        Err(response) => return response,
+    };
+    let (reply, response) = std_mpsc::sync_channel(1);
+    if requests.blocking_send(Request { call, reply }).is_err() {
+        return Response::message(503, "The server is stopping");
+    }
+    response
+        .recv()
+        .unwrap_or_else(|_| Response::message(503, "The request was dropped"))
+}
+
+fn authorized(request: &tiny_http::Request, token: &str) -> bool {
+    request.headers().iter().any(|header| {
+        header.field.equiv("Authorization")
+            && header
+                .value
+                .as_str()
+                .strip_prefix("Bearer ")
+                .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+    })
+}
+
+/// Compare the bytes in time which doesn't depend on where they differ, so
+/// the token can't be guessed byte by byte
+fn constant_time_eq(given: &[u8], expected: &[u8]) -> bool {
+    given.len() == expected.len()
+        && given
+            .iter()
+            .zip(expected)
+            .fold(0, |diff, (given, expected)| diff | (given ^ expected))
+            == 0
+}
+
+fn route(request: &mut tiny_http::Request) -> std::result::Result<Call, Response> {
+    let path = request.url().split('?').next().unwrap_or_default();
+    let segments = path
+        .trim_matches('/')
+        .split('/')
+        .map(|segment| {
+            percent_decode_str(segment)
+                .decode_utf8()
+                .map(Cow::into_owned)
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|_| Response::message(400, "The path is not valid UTF-8"))?;
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let method = request.method().clone();
+    let (name, action) = match (&method, segments.as_slice()) {
+        (Method::Get, ["media"]) => return Ok(Call::List),
+        (Method::Get, ["media", name]) => (name, Action::Show),
+        (Method::Patch, ["media", name]) => (name, Action::Change(read_changes(request)?)),
+        (Method::Post, ["media", name, "next"]) => (name, Action::Next),
+        (Method::Post, ["media", name, "watch"]) => (name, Action::Watch),
+        (_, ["media"] | ["media", _] | ["media", _, "next" | "watch"]) => {
+            return Err(Response::message(405, "Method is not allowed"));
+        }
+        _ => return Err(Response::message(404, "Unknown endpoint")),
+    };
+    let name = (*name).to_string();
+    Ok(Call::Media { name, action })
+}
+
+fn read_changes(request: &mut tiny_http::Request) -> std::result::Result<Changes, Response> {
+    let body = request.as_reader().take(MAX_BODY_LEN);
+    serde_json::from_reader(body).map_err(|err| Response::message(400, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_compared_whole() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secreT", b"secret"));
+        assert!(!constant_time_eq(b"secret!", b"secret"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+}
@@ -0,0 +1,316 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Commands for terminals and scripts which work without the GUI
+
+use std::path::{self, Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use clap::{Parser, Subcommand};
+use open_media_tracker::{
+    Config, MediaList, Result, Settings, find_resume_point, import_kodi, kodi_database,
+    watch_later_dir,
+};
+
+use crate::actions::{self, Changes};
+use crate::api::{self, Call, Response};
+use crate::error::{AppError, AppResult};
+use crate::report::{MediaReport, Report, ResumeReport};
+use crate::{mpris, tui};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Track watched episodes of series and audiobooks")]
+pub struct Cli {
+    /// Print the result as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+    /// Run the GUI if no command is given
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List all media
+    List,
+    /// Show the media and its current episode
+    Show { name: String },
+    /// Move to the next episode, the next chapter follows the last episode
+    Next { name: String },
+    /// Change the chapter, the episode or the paths of the media
+    Set {
+        name: String,
+        #[arg(long)]
+        chapter: Option<u8>,
+        #[arg(long)]
+        episode: Option<u8>,
+        /// Directory, playlist or archive with episodes of the chapter
+        #[arg(long)]
+        path: Option<PathBuf>,
+        /// Directory with chapters as subdirectories
+        #[arg(long)]
+        series_path: Option<PathBuf>,
+    },
+    /// Play the current episode
+    Watch { name: String },
+    /// Browse and change media in the terminal UI
+    Tui,
+    /// Answer requests to the remote API without the GUI
+    Serve,
+    /// Follow media players and update the media they play
+    Track,
+    /// Find where mpv stopped playing episodes of the media
+    ImportMpv {
+        /// Update the media instead of only showing the changes
+        #[arg(long)]
+        apply: bool,
+        /// mpv `watch_later` directory
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+    /// Create or update media from shows played in Kodi
+    ImportKodi {
+        /// Kodi video database, `MyVideos*.db`
+        #[arg(long)]
+        db: Option<PathBuf>,
+    },
+    /// Add new media
+    Add {
+        name: String,
+        /// Directory, playlist or archive with episodes of the chapter
+        #[arg(long)]
+        path: PathBuf,
+        /// Directory with chapters as subdirectories
+        #[arg(long)]
+        series_path: Option<PathBuf>,
+    },
+}
+
+/// Execute the command and print its result
+pub fn run(command: Command, json: bool) -> ExitCode {
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            eprintln!("Failed to start async runtime: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match runtime.block_on(execute(command, json)) {
+        Ok(report) => {
+            if let Some(report) = report {
+                report.print(json);
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn execute(command: Command, json: bool) -> AppResult<Option<Report>> {
+    let mut tracker = Tracker::read().await?;
+    let id = match command {
+        Command::List => return Ok(Some(Report::List(tracker.media_list_report()))),
+        Command::Tui => {
+            tui::run(tracker.config, tracker.settings, tracker.media_list).await?;
+            return Ok(None);
+        }
+        Command::Serve => {
+            tracker.serve().await?;
+            return Ok(None);
+        }
+        Command::Track => {
+            tracker.track(json).await?;
+            return Ok(None);
+        }
+        Command::ImportMpv { apply, dir } => {
+            let dir = dir
+                .or_else(watch_later_dir)
+                .ok_or(AppError::WatchLaterNotFound)?;
+            return Ok(Some(tracker.import_mpv(&dir, apply).await?));
+        }
+        Command::ImportKodi { db } => {
+            let db = db
+                .or_else(kodi_database)
+                .ok_or(AppError::KodiDatabaseNotFound)?;
+            let config = tracker.config.clone();
+            let changed = import_kodi(
+                &mut tracker.media_list,
+                db,
+                &tracker.settings,
+                config,
+                false,
+            )
+            .await?;
+            let reports = changed
+                .into_iter()
+                .map(|id| MediaReport::new(&tracker.media_list[id]))
+                .collect();
+            return Ok(Some(Report::List(reports)));
+        }
+        Command::Show { name } => tracker.find(&name)?,
+        Command::Next { name } => {
+            let id = tracker.find(&name)?;
+            actions::next(&mut tracker.media_list[id], &tracker.settings).await?;
+            id
+        }
+        Command::Set {
+            name,
+            chapter,
+            episode,
+            path,
+            series_path,
+        } => {
+            let id = tracker.find(&name)?;
+            let changes = Changes {
+                chapter,
+                episode,
+                chapter_path: path.map(path::absolute).transpose()?,
+                series_path: series_path.map(path::absolute).transpose()?,
+            };
+            actions::change(&mut tracker.media_list[id], &tracker.settings, changes).await?;
+            id
+        }
+        Command::Watch { name } => {
+            let id = tracker.find(&name)?;
+            let media = &mut tracker.media_list[id];
+            if let Some(warning) = actions::watch(media, &tracker.settings, &tracker.config).await?
+            {
+                eprintln!("{warning}");
+            }
+            id
+        }
+        Command::Add {
+            name,
+            path,
+            series_path,
+        } => {
+            let id = tracker.media_list.add(name, tracker.config.clone())?;
+            let media = &mut tracker.media_list[id];
+            media.set_chapter_path(path::absolute(path)?)?;
+            if let Some(series_path) = series_path {
+                media.set_series_path(Some(path::absolute(series_path)?))?;
+            }
+            media.reload_metadata().await?;
+            id
+        }
+    };
+    Ok(Some(tracker.report(id).await))
+}
+
+struct Tracker {
+    config: Arc<Config>,
+    settings: Settings,
+    media_list: MediaList,
+}
+
+impl Tracker {
+    async fn read() -> Result<Self> {
+        let config = Arc::new(Config::read()?);
+        let settings = Settings::read(&config)?;
+        let (media_list, error) = MediaList::read(config.clone()).await;
+        if let Some(err) = error {
+            log::warn!("{err}");
+        }
+        Ok(Self {
+            config,
+            settings,
+            media_list,
+        })
+    }
+
+    fn find(&self, name: &str) -> AppResult<usize> {
+        self.media_list
+            .position(name)
+            .ok_or_else(|| AppError::media_not_found(name))
+    }
+
+    async fn report(&mut self, id: usize) -> Report {
+        let report = actions::report(&mut self.media_list[id], &self.settings).await;
+        Report::Media(Box::new(report))
+    }
+
+    /// Answer API requests until the server stops
+    async fn serve(&mut self) -> AppResult<()> {
+        let mut requests = api::spawn(&self.settings.remote_api).await?;
+        eprintln!("Listening on {}", self.settings.remote_api.address);
+        while let Some(request) = requests.recv().await {
+            let response = match request.call.clone() {
+                Call::List => Response::ok(&self.media_list_report()),
+                Call::Media { name, action } => match self.find(&name) {
+                    Ok(id) => {
+                        let media = &mut self.media_list[id];
+                        api::perform(action, media, &self.settings, &self.config)
+                            .await
+                            .into()
+                    }
+                    Err(err) => Response::error(&err),
+                },
+            };
+            request.reply(response);
+        }
+        Ok(())
+    }
+
+    /// Follow players until the session bus is closed. The media is printed
+    /// when its episode changes
+    async fn track(&mut self, json: bool) -> AppResult<()> {
+        let mut players = mpris::spawn().await?;
+        while let Some(playing) = players.recv().await {
+            for id in mpris::candidates(&self.media_list, &playing.path) {
+                let media = &mut self.media_list[id];
+                let episode = media.episode();
+                match mpris::follow(media, &self.settings, &playing).await {
+                    Ok(true) if media.episode() != episode => self.report(id).await.print(json),
+                    Ok(_) => {}
+                    Err(err) => log::warn!("{err}"),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Media which mpv stopped on other episodes or positions. They are
+    /// updated if `apply` is set
+    async fn import_mpv(&mut self, dir: &Path, apply: bool) -> Result<Report> {
+        let mut reports = Vec::new();
+        for media in self.media_list.iter_mut() {
+            if media.chapter_path().as_os_str().is_empty() {
+                continue;
+            }
+            let point = match media.episode_list(&self.settings).await {
+                Ok(episodes) => find_resume_point(media, &episodes, dir).await,
+                Err(err) => Err(err),
+            };
+            let point = match point {
+                Ok(Some(point)) => point,
+                Ok(None) => continue,
+                Err(err) => {
+                    log::warn!("{}: {err}", media.name());
+                    continue;
+                }
+            };
+            if apply {
+                media.resume_at(&point)?;
+            }
+            reports.push(ResumeReport::new(media.name(), &point));
+        }
+        if !apply && !reports.is_empty() {
+            eprintln!("Run with --apply to update the media");
+        }
+        Ok(Report::Resume(reports))
+    }
+
+    fn media_list_report(&self) -> Vec<MediaReport> {
+        self.media_list.iter().map(MediaReport::new).collect()
+    }
+}
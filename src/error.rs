@@ -0,0 +1,52 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::io;
+
+use open_media_tracker::ErrorKind;
+
+/// Errors of the frontends along with the ones of the library
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum AppError {
+    #[error(transparent)]
+    Library(#[from] ErrorKind),
+    #[error("Media \"{name}\" not found")]
+    MediaNotFound { name: String },
+    #[error("{address}: Failed to start API server: {message}")]
+    ApiServer { address: String, message: String },
+    #[error("Kodi video database not found")]
+    KodiDatabaseNotFound,
+    #[error("Failed to follow media players: {message}")]
+    TrackPlayers { message: String },
+    #[error("mpv watch_later directory not found")]
+    WatchLaterNotFound,
+}
+
+impl AppError {
+    pub fn media_not_found(name: impl Into<String>) -> Self {
+        Self::MediaNotFound { name: name.into() }
+    }
+
+    pub fn api_server(address: impl Into<String>, message: impl Into<String>) -> Self {
+        let address = address.into();
+        let message = message.into();
+        Self::ApiServer { address, message }
+    }
+
+    pub fn track_players(message: impl Into<String>) -> Self {
+        Self::TrackPlayers {
+            message: message.into(),
+        }
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(value: io::Error) -> Self {
+        Self::Library(value.into())
+    }
+}
+
+pub type AppResult<T> = std::result::Result<T, AppError>;
@@ -4,12 +4,26 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use std::fmt::Write;
+
 use derive_more::Display;
 
+use super::progress::Proposal;
+
 #[derive(Clone, Display)]
 pub enum ConfirmKind {
     #[display("You actually want to delete media \"{name}\" from the list?")]
     DeleteMedia { name: String, id: usize },
+    #[display("{}", proposals_text(proposals))]
+    ImportProgress { proposals: Vec<Proposal> },
+}
+
+fn proposals_text(proposals: &[Proposal]) -> String {
+    let mut text = String::from("Mark as current?");
+    for proposal in proposals {
+        let _ = write!(text, "\n{proposal}");
+    }
+    text
 }
 
 #[derive(PartialEq, Eq, Hash)]
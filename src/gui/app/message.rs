@@ -5,11 +5,15 @@
  */
 
 use derive_more::derive::From;
+use open_media_tracker::{ErrorKind, MediaHandler, MediaList};
 
+use super::overview::Overview;
+use super::progress::Proposal;
+use crate::error::AppError;
 use crate::gui::page::{
-    ConfirmPageMsg, ErrorPageMsg, LoadingPageMsg, MainPageMsg, MediaEditPageMsg,
+    ConfirmPageMsg, ErrorPageMsg, LoadingPageMsg, MainPageMsg, MediaEditPageMsg, SettingsPageMsg,
 };
-use crate::model::{ErrorKind, MaybeError, MediaList};
+use crate::{api, mpris};
 
 #[derive(Debug, Clone, From)]
 pub enum Msg {
@@ -17,10 +21,32 @@ pub enum Msg {
     MediaEditScreen(MediaEditPageMsg),
     ConfirmScreen(ConfirmPageMsg),
     ErrorScreen(ErrorPageMsg),
-    MediaLoaded(MaybeError<MediaList, ErrorKind>),
+    SettingsScreen(SettingsPageMsg),
+    MediaLoaded(MediaList, Option<ErrorKind>),
     SelectMedia(String),
     CreateMedia,
+    OpenSettings,
     Loading,
+    RemoteStarted,
+    RemoteFailed(AppError),
+    RemoteRequest(api::Request),
+    TrackingFailed(AppError),
+    Playing(mpris::Playing),
+    /// Detached copy of the media before and after it was changed outside of
+    /// the GUI
+    MediaChanged(MediaHandler, MediaHandler),
+    /// Detached copy of the media left on the media page and the copy with
+    /// metadata read for its paths
+    #[from(skip)]
+    MetadataReloaded(MediaHandler, MediaHandler),
+    /// Media changed by the Kodi import: its detached copy before the import,
+    /// `None` if the import created it, and after the import
+    KodiImported(Result<Vec<(Option<MediaHandler>, MediaHandler)>, ErrorKind>),
+    /// Episodes played in other applications
+    ProgressFound(Vec<Proposal>),
+    /// Details of the media with the name loaded in the background
+    OverviewLoaded(String, Overview),
+    TogglePosterGrid,
 }
 
 impl From<LoadingPageMsg> for Msg {
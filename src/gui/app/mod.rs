@@ -6,24 +6,38 @@
 
 mod kinds;
 mod message;
+mod overview;
+mod progress;
 mod screens;
 
 use std::sync::Arc;
 
 use cosmic::app::Task;
-use cosmic::iced::{executor, window};
+use cosmic::iced::futures::{SinkExt, Stream};
+use cosmic::iced::{Subscription, executor, stream, window};
 use cosmic::widget::Popover;
 use cosmic::{Action, Application, Core, Element};
+use open_media_tracker::{
+    Config, ErrorKind, MediaHandler, MediaList, RemoteApi, Settings, import_kodi, kodi_database,
+    remove_unused_thumbnails,
+};
 
+use crate::api::{self, Call, Response};
+use crate::error::AppError;
 use crate::gui::page::{
-    ConfirmDlg, ConfirmPageMsg, ErrorPage, ErrorPageMsg, MainPage, MediaEditPageMsg,
+    ConfirmDlg, ConfirmPageMsg, ErrorPage, ErrorPageMsg, MainPage, MediaEditPageMsg, SettingsPage,
+    SettingsPageMsg,
 };
-use crate::gui::{Dialog, LoadingDialog, Page};
-use crate::model::{Config, Error, ErrorKind, MediaHandler, MediaList, Placeholder};
+use crate::gui::{Dialog, Error, LoadingDialog, Page};
+use crate::mpris;
+use crate::report::MediaReport;
 use kinds::{ConfirmKind, LoadingKind};
 pub use message::Msg;
+pub use overview::{Overview, Overviews};
 use screens::Screens;
 
+const SUBSCRIPTION_BUFFER: usize = 16;
+
 pub struct OpenMediaTracker {
     core: Core,
     media_list: MediaList,
@@ -32,6 +46,8 @@ pub struct OpenMediaTracker {
     error: Dialog<ErrorPage>,
     loading: LoadingDialog<LoadingKind>,
     config: Arc<Config>,
+    settings: Arc<Settings>,
+    overviews: Overviews,
 }
 
 impl Application for OpenMediaTracker {
@@ -61,6 +77,14 @@ impl Application for OpenMediaTracker {
                 screen = Screens::error(Error::fatal(err));
             }
         }
+        let mut error = Dialog::closed();
+        let settings = match Settings::read(&config) {
+            Ok(settings) => settings,
+            Err(err) => {
+                error = Dialog::new(Error::common(err).into());
+                Settings::default()
+            }
+        };
         let config = config.into();
         // TODO: Uncoment when header bar will be fixed
         // core.window.header_title = String::from("Open Media Tracker");
@@ -69,9 +93,11 @@ impl Application for OpenMediaTracker {
             media_list: MediaList::new(),
             screen,
             confirm: ConfirmDlg::closed(),
-            error: Dialog::closed(),
+            error,
             loading: LoadingDialog::closed(),
             config,
+            settings: settings.into(),
+            overviews: Overviews::new(),
         };
         let task = omt.read_media();
         (omt, task)
@@ -96,6 +122,22 @@ impl Application for OpenMediaTracker {
         screen_view
     }
 
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let mut subscriptions = Vec::new();
+        let remote_api = &self.settings.remote_api;
+        if remote_api.enabled {
+            // The server is restarted whenever its settings change
+            subscriptions.push(Subscription::run_with_id(
+                remote_api.clone(),
+                remote_requests(remote_api.clone()),
+            ));
+        }
+        if self.settings.track_players {
+            subscriptions.push(Subscription::run_with_id("track-players", playing_files()));
+        }
+        Subscription::batch(subscriptions)
+    }
+
     fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
         match self.update2(message) {
             Ok(task) => return task,
@@ -107,13 +149,37 @@ impl Application for OpenMediaTracker {
 
 impl OpenMediaTracker {
     fn change_media_screen(&mut self, id: usize) -> Task<Msg> {
-        let (screen, task) = Screens::change_media(&self.media_list, id);
+        let (screen, task) = Screens::change_media(
+            &self.media_list,
+            id,
+            self.settings.clone(),
+            self.config.clone(),
+        );
         self.screen = screen;
         task.map(Action::App)
     }
 
     fn main_screen(&mut self) {
-        self.screen = Screens::Main(MainPage::new(&self.media_list));
+        self.screen = Screens::Main(MainPage::new(
+            &self.media_list,
+            self.overviews.clone(),
+            self.settings.poster_grid,
+        ));
+    }
+
+    fn settings_screen_update(&mut self, message: SettingsPageMsg) -> Result<(), ErrorKind> {
+        if matches!(message, SettingsPageMsg::Back) {
+            self.main_screen();
+            return Ok(());
+        }
+        let Screens::Settings(screen) = &mut self.screen else {
+            return Ok(());
+        };
+        let settings = Arc::make_mut(&mut self.settings);
+        if screen.update(message, settings) {
+            settings.save(&self.config)?;
+        }
+        Ok(())
     }
 
     fn error_dialog(&mut self, error: Error) {
@@ -127,7 +193,10 @@ impl OpenMediaTracker {
     fn read_media(&mut self) -> Task<Msg> {
         self.loading.insert(LoadingKind::ReadMedia);
         let config = self.config.clone();
-        cosmic::task::future(async move { Msg::MediaLoaded(MediaList::read(config).await) })
+        cosmic::task::future(async move {
+            let (media_list, error) = MediaList::read(config).await;
+            Msg::MediaLoaded(media_list, error)
+        })
     }
 
     fn confirm_screen_update(&mut self, message: &ConfirmPageMsg) -> Result<(), ErrorKind> {
@@ -148,6 +217,17 @@ impl OpenMediaTracker {
                 self.confirm.close();
                 self.main_screen();
             }
+            ConfirmKind::ImportProgress { proposals } => {
+                for proposal in proposals {
+                    if let Some(id) = self.media_list.position(proposal.name()) {
+                        proposal.apply(&mut self.media_list[id])?;
+                    }
+                }
+                self.confirm.close();
+                if let Screens::Main(screen) = &mut self.screen {
+                    screen.update_media(&self.media_list);
+                }
+            }
         }
         Ok(())
     }
@@ -162,7 +242,16 @@ impl OpenMediaTracker {
                 let name = media.name().to_string();
                 self.confirm_dialog(ConfirmKind::DeleteMedia { id, name });
             }
-            MediaEditPageMsg::Back => self.main_screen(),
+            MediaEditPageMsg::Back => {
+                let task = match &self.screen {
+                    Screens::MediaChange(screen) => {
+                        self.media_list.get(screen.media_id()).map(reload_metadata)
+                    }
+                    _ => None,
+                };
+                self.main_screen();
+                return Ok(task.unwrap_or_else(Task::none));
+            }
             _ => {
                 if let Screens::MediaChange(dialog) = &mut self.screen {
                     let task = dialog.update(&mut self.media_list, message)?;
@@ -173,6 +262,214 @@ impl OpenMediaTracker {
         Ok(Task::none())
     }
 
+    /// Answer the remote request. Media is changed on its detached copy which
+    /// is then merged into the original
+    fn remote_request(&self, request: api::Request) -> Task<Msg> {
+        let (name, action) = match request.call.clone() {
+            Call::List => {
+                let media: Vec<_> = self.media_list.iter().map(MediaReport::new).collect();
+                request.reply(Response::ok(&media));
+                return Task::none();
+            }
+            Call::Media { name, action } => (name, action),
+        };
+        let Some(id) = self.media_list.position(&name) else {
+            request.reply(Response::error(&AppError::media_not_found(name)));
+            return Task::none();
+        };
+        let base = self.media_list[id].detached();
+        let mut media = base.clone();
+        let settings = self.settings.clone();
+        let config = self.config.clone();
+        cosmic::task::future(async move {
+            let result = api::perform(action, &mut media, &settings, &config).await;
+            request.reply(result.into());
+            Msg::MediaChanged(base, media)
+        })
+    }
+
+    /// Move media to the file playing in another player
+    fn follow_player(&self, playing: &mpris::Playing) -> Task<Msg> {
+        let tasks = mpris::candidates(&self.media_list, &playing.path)
+            .into_iter()
+            .map(|id| {
+                let base = self.media_list[id].detached();
+                let mut media = base.clone();
+                let settings = self.settings.clone();
+                let playing = playing.clone();
+                cosmic::task::future(async move {
+                    if let Err(err) = mpris::follow(&mut media, &settings, &playing).await {
+                        log::warn!("{err}");
+                    }
+                    Msg::MediaChanged(base, media)
+                })
+            });
+        Task::batch(tasks)
+    }
+
+    /// Merge the copy changed in the background into the media, which is
+    /// looked up by name again as it may have been renamed or removed
+    /// meanwhile. Pages are refreshed only if the current episode has moved
+    fn media_changed(
+        &mut self,
+        base: &MediaHandler,
+        changed: &MediaHandler,
+    ) -> Result<Task<Msg>, ErrorKind> {
+        let Some(id) = self.media_list.position(base.name()) else {
+            return Ok(Task::none());
+        };
+        let old = self.media_list[id].clone();
+        self.media_list[id].merge(base, changed)?;
+        let media = &self.media_list[id];
+        let new_chapter = old.chapter_path() != media.chapter_path();
+        let moved =
+            new_chapter || old.chapter() != media.chapter() || old.episode() != media.episode();
+        if !moved {
+            return Ok(Task::none());
+        }
+        // Episodes of the new chapter are counted again
+        let overview = if new_chapter {
+            self.load_overviews(vec![self.media_list[id].clone()], false)
+        } else {
+            Task::none()
+        };
+        match &mut self.screen {
+            Screens::Main(screen) => screen.update_media(&self.media_list),
+            Screens::MediaChange(screen) if screen.media_id() == id => {
+                return Ok(Task::batch([overview, self.change_media_screen(id)]));
+            }
+            _ => {}
+        }
+        Ok(overview)
+    }
+
+    /// Load overviews of the media one by one, so the main list fills in
+    /// while the rest is loaded. On `startup` the same episodes are used to
+    /// look for progress made in other applications, and thumbnails of other
+    /// media are removed
+    fn load_overviews(&self, media_list: Vec<MediaHandler>, startup: bool) -> Task<Msg> {
+        if media_list.is_empty() {
+            return Task::none();
+        }
+        let settings = self.settings.clone();
+        let config = self.config.clone();
+        let overviews = stream::channel(SUBSCRIPTION_BUFFER, |mut output| async move {
+            let sources = if startup {
+                progress::Sources::read().await
+            } else {
+                progress::Sources::default()
+            };
+            let names: Vec<_> = media_list
+                .iter()
+                .map(|media| media.name().to_string())
+                .collect();
+            let mut proposals = Vec::new();
+            for media in media_list {
+                let episodes = Overview::read_episodes(&media, &settings).await;
+                if startup && let Some(episodes) = &episodes {
+                    proposals.extend(progress::propose(&media, episodes, &sources).await);
+                }
+                let overview = Overview::load(&media, episodes.as_ref(), &config).await;
+                let loaded = Msg::OverviewLoaded(media.name().to_string(), overview);
+                if output.send(loaded).await.is_err() {
+                    return;
+                }
+            }
+            if startup {
+                let names = names.iter().map(String::as_str);
+                if let Err(err) = remove_unused_thumbnails(&config, names) {
+                    log::warn!("{err}");
+                }
+                let _ = output.send(Msg::ProgressFound(proposals)).await;
+            }
+        });
+        Task::run(overviews, Action::App)
+    }
+
+    fn overview_loaded(&mut self, name: String, overview: Overview) {
+        if let Screens::Main(screen) = &mut self.screen {
+            screen.set_overview(name.clone(), overview.clone());
+        }
+        self.overviews.insert(name, overview);
+    }
+
+    /// Import shows from Kodi into a detached copy of the media list. The
+    /// changes are merged once the import is done
+    fn import_kodi(&self) -> Result<Task<Msg>, AppError> {
+        let db = kodi_database().ok_or(AppError::KodiDatabaseNotFound)?;
+        let base: Vec<_> = self.media_list.iter().map(MediaHandler::detached).collect();
+        let mut media_list = MediaList::from(base.clone());
+        let settings = self.settings.clone();
+        let config = self.config.clone();
+        let task = cosmic::task::future(async move {
+            let result = import_kodi(&mut media_list, db, &settings, config, true)
+                .await
+                .map(|mut changed| {
+                    changed.sort_unstable();
+                    changed.dedup();
+                    // Media created by the import is appended to the list
+                    changed
+                        .into_iter()
+                        .map(|id| (base.get(id).cloned(), media_list[id].clone()))
+                        .collect()
+                });
+            Msg::KodiImported(result)
+        });
+        Ok(task)
+    }
+
+    /// Merge the imported shows into the media found by name, since the list
+    /// may have changed during the import. The open media page is reloaded
+    /// if its media was changed
+    fn kodi_imported(
+        &mut self,
+        imported: Vec<(Option<MediaHandler>, MediaHandler)>,
+    ) -> Result<Task<Msg>, ErrorKind> {
+        let mut changed = Vec::with_capacity(imported.len());
+        for (base, mut media) in imported {
+            let id = match base {
+                Some(base) => {
+                    let Some(id) = self.media_list.position(base.name()) else {
+                        continue;
+                    };
+                    self.media_list[id].merge(&base, &media)?;
+                    id
+                }
+                None if self.media_list.position(media.name()).is_none() => {
+                    media.attach()?;
+                    self.media_list.insert(media)
+                }
+                None => continue,
+            };
+            changed.push(id);
+        }
+        let media_list = changed
+            .iter()
+            .map(|&id| self.media_list[id].clone())
+            .collect();
+        let overview = self.load_overviews(media_list, false);
+        match &mut self.screen {
+            Screens::Settings(screen) => {
+                screen.set_kodi_status(format!("Imported shows: {}", changed.len()));
+            }
+            Screens::Main(screen) => screen.update_media(&self.media_list),
+            Screens::MediaChange(screen) if changed.contains(&screen.media_id()) => {
+                let id = screen.media_id();
+                return Ok(Task::batch([overview, self.change_media_screen(id)]));
+            }
+            _ => {}
+        }
+        Ok(overview)
+    }
+
+    fn remote_status(&mut self, error: Option<AppError>) {
+        if let Screens::Settings(screen) = &mut self.screen {
+            screen.set_remote_error(error);
+        } else if let Some(error) = error {
+            self.error_dialog(error.into());
+        }
+    }
+
     fn update2(&mut self, message: Msg) -> Result<Task<Msg>, Error> {
         match message {
             Msg::MainScreen(message) => {
@@ -190,15 +487,23 @@ impl OpenMediaTracker {
                 self.error.close();
             }
             Msg::ConfirmScreen(message) => self.confirm_screen_update(&message)?,
-            Msg::MediaLoaded(res) => {
-                self.media_list = res.value;
-                if let Some(err) = res.error {
+            Msg::SettingsScreen(SettingsPageMsg::ImportKodi) => return Ok(self.import_kodi()?),
+            Msg::SettingsScreen(message) => self.settings_screen_update(message)?,
+            Msg::OpenSettings => {
+                self.screen = Screens::Settings(SettingsPage::new(&self.settings));
+            }
+            Msg::MediaLoaded(media_list, error) => {
+                self.media_list = media_list;
+                if let Some(err) = error {
                     self.error_dialog(err.into());
                 }
                 self.loading.complete(&LoadingKind::ReadMedia);
                 if let Screens::Main(screen) = &mut self.screen {
+                    screen.set_poster_grid(self.settings.poster_grid);
                     screen.update_media(&self.media_list);
                 }
+                let media_list = self.media_list.iter().cloned().collect();
+                return Ok(self.load_overviews(media_list, true));
             }
             Msg::Loading => {}
             Msg::SelectMedia(media_name) => {
@@ -212,6 +517,35 @@ impl OpenMediaTracker {
                     return Ok(self.change_media_screen(id));
                 }
             }
+            Msg::RemoteStarted => self.remote_status(None),
+            Msg::RemoteFailed(err) => self.remote_status(Some(err)),
+            Msg::RemoteRequest(request) => return Ok(self.remote_request(request)),
+            Msg::TrackingFailed(err) => self.error_dialog(err.into()),
+            Msg::Playing(playing) => return Ok(self.follow_player(&playing)),
+            Msg::MediaChanged(base, changed) => return Ok(self.media_changed(&base, &changed)?),
+            Msg::KodiImported(result) => return Ok(self.kodi_imported(result?)?),
+            Msg::MetadataReloaded(base, changed) => {
+                let Some(id) = self.media_list.position(base.name()) else {
+                    return Ok(Task::none());
+                };
+                self.media_list[id].merge(&base, &changed)?;
+                // The cover and the chapter may be changed along with the media
+                return Ok(self.load_overviews(vec![self.media_list[id].clone()], false));
+            }
+            Msg::OverviewLoaded(name, overview) => self.overview_loaded(name, overview),
+            Msg::TogglePosterGrid => {
+                let settings = Arc::make_mut(&mut self.settings);
+                settings.poster_grid = !settings.poster_grid;
+                settings.save(&self.config)?;
+                if let Screens::Main(screen) = &mut self.screen {
+                    screen.set_poster_grid(settings.poster_grid);
+                }
+            }
+            Msg::ProgressFound(proposals) => {
+                if !proposals.is_empty() {
+                    self.confirm_dialog(ConfirmKind::ImportProgress { proposals });
+                }
+            }
             Msg::CreateMedia => {
                 let config = self.config.clone();
                 let media = MediaHandler::with_default_name(config)?;
@@ -223,6 +557,57 @@ impl OpenMediaTracker {
     }
 }
 
+/// Start the API server and forward its requests
+fn remote_requests(settings: RemoteApi) -> impl Stream<Item = Msg> {
+    stream::channel(SUBSCRIPTION_BUFFER, |mut output| async move {
+        let mut requests = match api::spawn(&settings).await {
+            Ok(requests) => requests,
+            Err(err) => {
+                let _ = output.send(Msg::RemoteFailed(err)).await;
+                return;
+            }
+        };
+        if output.send(Msg::RemoteStarted).await.is_err() {
+            return;
+        }
+        while let Some(request) = requests.recv().await {
+            if output.send(Msg::RemoteRequest(request)).await.is_err() {
+                return;
+            }
+        }
+    })
+}
+
+/// Follow media players and forward files they play
+fn playing_files() -> impl Stream<Item = Msg> {
+    stream::channel(SUBSCRIPTION_BUFFER, |mut output| async move {
+        let mut players = match mpris::spawn().await {
+            Ok(players) => players,
+            Err(err) => {
+                let _ = output.send(Msg::TrackingFailed(err)).await;
+                return;
+            }
+        };
+        while let Some(playing) = players.recv().await {
+            if output.send(Msg::Playing(playing)).await.is_err() {
+                return;
+            }
+        }
+    })
+}
+
+/// Paths typed on the media page are committed when the page is left
+fn reload_metadata(media: &MediaHandler) -> Task<Msg> {
+    let base = media.detached();
+    let mut changed = base.clone();
+    cosmic::task::future(async move {
+        if let Err(err) = changed.reload_metadata().await {
+            log::warn!("{err}");
+        }
+        Msg::MetadataReloaded(base, changed)
+    })
+}
+
 fn close_app() -> Task<Msg> {
     window::get_latest().and_then(window::close)
 }
@@ -0,0 +1,99 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Details of media shown in the main list which are slow to find, so they
+//! are loaded in the background
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use open_media_tracker::{Config, EpisodeList, MediaHandler, Settings, thumbnail};
+
+/// Overviews by media names
+pub type Overviews = HashMap<String, Overview>;
+
+#[derive(Debug, Clone, Default)]
+pub struct Overview {
+    /// Thumbnail of the media cover
+    pub thumbnail: Option<PathBuf>,
+    /// Number of the first episode of the current chapter
+    pub first_episode: Option<u8>,
+    /// Number of the last episode of the current chapter
+    pub last_episode: Option<usize>,
+}
+
+impl Overview {
+    /// Find the cover and count `episodes` of the current chapter. Errors
+    /// are logged, the list works without overviews
+    pub async fn load(
+        media: &MediaHandler,
+        episodes: Option<&EpisodeList>,
+        config: &Config,
+    ) -> Self {
+        let no_episodes = EpisodeList::default();
+        let thumbnail = thumbnail(media, episodes.unwrap_or(&no_episodes), config)
+            .await
+            .inspect_err(|err| log::warn!("{}: {err}", media.name()))
+            .ok()
+            .flatten();
+        let first_episode = episodes.map(EpisodeList::first_number);
+        let last_episode = episodes.and_then(EpisodeList::last_number);
+        Self {
+            thumbnail,
+            first_episode,
+            last_episode,
+        }
+    }
+
+    /// Read episodes of the current chapter once for everything loaded in
+    /// the background. Errors are logged
+    pub async fn read_episodes(media: &MediaHandler, settings: &Settings) -> Option<EpisodeList> {
+        if media.chapter_path().as_os_str().is_empty() {
+            return None;
+        }
+        media
+            .episode_list(settings)
+            .await
+            .inspect_err(|err| log::warn!("{}: {err}", media.name()))
+            .ok()
+    }
+
+    /// Part of the chapter before the current episode. Chapters numbered
+    /// on from the previous one start after 1
+    pub fn progress(&self, episode: u8) -> Option<f32> {
+        let first_episode = u16::from(self.first_episode?);
+        let last_episode = u16::try_from(self.last_episode?).unwrap_or(u16::MAX - 1);
+        let count = (last_episode + 1)
+            .checked_sub(first_episode)
+            .filter(|count| *count > 0)?;
+        let watched = u16::from(episode).saturating_sub(first_episode).min(count);
+        Some(f32::from(watched) / f32::from(count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overview(first_episode: u8, last_episode: usize) -> Overview {
+        Overview {
+            first_episode: Some(first_episode),
+            last_episode: Some(last_episode),
+            ..Overview::default()
+        }
+    }
+
+    #[test]
+    fn progress_counts_from_first_episode() {
+        assert_eq!(overview(1, 4).progress(1), Some(0.0));
+        assert_eq!(overview(1, 4).progress(3), Some(0.5));
+        assert_eq!(overview(13, 24).progress(13), Some(0.0));
+        assert_eq!(overview(13, 24).progress(19), Some(0.5));
+        assert_eq!(overview(13, 24).progress(30), Some(1.0));
+        assert_eq!(overview(1, 0).progress(1), None);
+        assert_eq!(Overview::default().progress(1), None);
+    }
+}
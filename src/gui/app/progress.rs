@@ -0,0 +1,89 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Progress made in other applications which is offered on start
+
+use std::path::PathBuf;
+
+use derive_more::Display;
+use open_media_tracker::utils::{format_day, format_duration};
+use open_media_tracker::{
+    EpisodeList, MediaHandler, RecentEpisode, RecentFile, Result, ResumePoint, find_recent_episode,
+    find_resume_point, read_recently_used, recently_used_file, watch_later_dir,
+};
+
+#[derive(Debug, Clone, Display)]
+pub enum Proposal {
+    #[display(
+        "{name}: mpv stopped at episode {} at {}",
+        point.episode,
+        format_duration(point.position)
+    )]
+    Mpv { name: String, point: ResumePoint },
+    #[display("{name}: you opened {} {}", episode.name, format_day(episode.opened))]
+    Opened {
+        name: String,
+        episode: RecentEpisode,
+    },
+}
+
+impl Proposal {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Mpv { name, .. } | Self::Opened { name, .. } => name,
+        }
+    }
+
+    pub fn apply(&self, media: &mut MediaHandler) -> Result<()> {
+        match self {
+            Self::Mpv { point, .. } => media.resume_at(point),
+            Self::Opened { episode, .. } => media.set_episode(episode.episode),
+        }
+    }
+}
+
+/// Progress stored by other applications, read once for all media
+#[derive(Debug, Default)]
+pub struct Sources {
+    watch_later_dir: Option<PathBuf>,
+    recent: Vec<RecentFile>,
+}
+
+impl Sources {
+    pub async fn read() -> Self {
+        let recent = match recently_used_file() {
+            Some(path) => read_recently_used(path)
+                .await
+                .inspect_err(|err| log::warn!("{err}"))
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+        Self {
+            watch_later_dir: watch_later_dir(),
+            recent,
+        }
+    }
+}
+
+/// Look for `episodes` of the media played in mpv or opened in any
+/// application since the media was changed. mpv is preferred as it knows
+/// the position
+pub async fn propose(
+    media: &MediaHandler,
+    episodes: &EpisodeList,
+    sources: &Sources,
+) -> Option<Proposal> {
+    let name = media.name().to_string();
+    if let Some(dir) = &sources.watch_later_dir {
+        match find_resume_point(media, episodes, dir).await {
+            Ok(Some(point)) => return Some(Proposal::Mpv { name, point }),
+            Ok(None) => {}
+            Err(err) => log::warn!("{name}: {err}"),
+        }
+    }
+    let episode = find_recent_episode(media, episodes, &sources.recent)?;
+    Some(Proposal::Opened { name, episode })
+}
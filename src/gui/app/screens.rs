@@ -4,32 +4,41 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use std::sync::Arc;
+
 use cosmic::{Element, Task};
 use derive_more::derive::From;
+use open_media_tracker::{Config, MediaList, MediaListRef, Settings};
 
-use crate::gui::Page;
 use crate::gui::app::Msg;
-use crate::gui::page::{ErrorPage, MainPage, MediaEditPage};
-use crate::model::{Error, MediaList, MediaListRef};
+use crate::gui::page::{ErrorPage, MainPage, MediaEditPage, SettingsPage};
+use crate::gui::{Error, Page};
 
 #[derive(From)]
 pub enum Screens {
     Main(MainPage),
     MediaChange(MediaEditPage),
     Error(ErrorPage),
+    Settings(SettingsPage),
 }
 
 impl Screens {
     pub fn view<'a>(&'a self, media: &'a MediaList) -> Element<'a, Msg> {
         match self {
-            Self::Main(screen) => screen.view_into(),
+            Self::Main(screen) => screen.view(media).map(Into::into),
             Self::MediaChange(screen) => screen.view(media).map(Into::into),
             Self::Error(screen) => screen.view_into(),
+            Self::Settings(screen) => screen.view_into(),
         }
     }
 
-    pub fn change_media(media: MediaListRef, id: usize) -> (Self, Task<Msg>) {
-        let (screen, task) = MediaEditPage::new(media, id);
+    pub fn change_media(
+        media: MediaListRef,
+        id: usize,
+        settings: Arc<Settings>,
+        config: Arc<Config>,
+    ) -> (Self, Task<Msg>) {
+        let (screen, task) = MediaEditPage::new(media, id, settings, config);
         (Self::MediaChange(screen), task.map(Msg::MediaEditScreen))
     }
 
@@ -0,0 +1,46 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use derive_more::{Deref, From};
+use open_media_tracker::{Episode, EpisodeList, ErrorKind};
+
+use crate::gui::LoadedData;
+
+#[derive(Debug, Clone, From, Deref)]
+pub struct Episodes(pub LoadedData<Arc<EpisodeList>, ErrorKind>);
+
+impl Episodes {
+    /// Find episode by its number, see [`EpisodeList::find`]
+    pub fn find(&self, number: u8) -> Option<std::result::Result<&Episode, &ErrorKind>> {
+        let res = self
+            .0
+            .as_opt_res()?
+            .and_then(|episodes| episodes.find(number).ok_or(&ErrorKind::EpisodeNotFound));
+        Some(res)
+    }
+
+    /// Number of the first episode on the disk, see [`EpisodeList::first_number`]
+    pub fn first_number(&self) -> Option<u8> {
+        self.0.as_option().map(|episodes| episodes.first_number())
+    }
+
+    /// Number of the last episode on the disk, see [`EpisodeList::last_number`]
+    pub fn last_number(&self) -> Option<usize> {
+        self.0
+            .as_option()
+            .and_then(|episodes| episodes.last_number())
+    }
+
+    /// Duration from the beginning of the episode to the end of the chapter
+    pub fn remaining_duration(&self, number: u8) -> Option<Duration> {
+        self.0
+            .as_option()
+            .and_then(|episodes| episodes.remaining_duration(number))
+    }
+}
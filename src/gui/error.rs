@@ -0,0 +1,42 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use derive_more::Display;
+use open_media_tracker::ErrorKind;
+
+use crate::error::AppError;
+
+/// Error shown in the dialog. Fatal error closes the application
+#[derive(Display)]
+#[display("{}", self.kind)]
+pub struct Error {
+    pub kind: AppError,
+    pub fatal: bool,
+}
+
+impl Error {
+    pub fn fatal(kind: impl Into<AppError>) -> Self {
+        let kind = kind.into();
+        Self { kind, fatal: true }
+    }
+
+    pub fn common(kind: impl Into<AppError>) -> Self {
+        let kind = kind.into();
+        Self { kind, fatal: false }
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(value: ErrorKind) -> Self {
+        Self::common(value)
+    }
+}
+
+impl From<AppError> for Error {
+    fn from(value: AppError) -> Self {
+        Self::common(value)
+    }
+}
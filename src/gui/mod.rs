@@ -9,9 +9,17 @@ pub mod dialog;
 pub mod page;
 pub mod utils;
 
+mod episodes;
+mod error;
 mod icon;
+mod loaded_data;
 mod loading;
+mod loading_queue;
 
 pub use dialog::Dialog;
+pub use episodes::Episodes;
+pub use error::Error;
+pub use loaded_data::LoadedData;
 pub use loading::LoadingDialog;
+pub use loading_queue::LoadingQueue;
 pub use page::Page;
@@ -8,8 +8,7 @@ use cosmic::Element;
 use cosmic::widget::{button, dialog, icon};
 use derive_more::From;
 
-use crate::gui::Page;
-use crate::model::Error;
+use crate::gui::{Error, Page};
 
 #[derive(Debug, Clone)]
 pub enum Msg {
@@ -11,8 +11,7 @@ use cosmic::iced::Length;
 use cosmic::iced_widget::center;
 use cosmic::widget::text;
 
-use crate::gui::Page;
-use crate::model::LoadingQueue;
+use crate::gui::{LoadingQueue, Page};
 
 pub struct Msg;
 
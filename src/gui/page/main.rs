@@ -4,23 +4,39 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use std::collections::HashMap;
+use std::path::Path;
+
 use cosmic::iced::{Alignment, Length};
 use cosmic::iced_widget::{column, row};
-use cosmic::widget::{Space, button, container, scrollable, segmented_button};
-use cosmic::{Element, Task, theme};
+use cosmic::widget::{
+    Column, Space, button, container, flex_row, horizontal_space, image, progress_bar, scrollable,
+    segmented_button, text,
+};
+use cosmic::{Element, Task, font, style, theme};
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
+use open_media_tracker::utils::format_ago;
+use open_media_tracker::{MediaHandler, MediaListRef, MediaListRefMut};
 
+use crate::gui::app::{Overview, Overviews};
 use crate::gui::utils::search_bar;
-use crate::gui::{self, Page, app};
-use crate::model::{MediaHandler, MediaListRef, MediaListRefMut};
+use crate::gui::{self, app};
+
+const COVER_WIDTH: f32 = 40.0;
+const COVER_HEIGHT: f32 = 60.0;
+const POSTER_WIDTH: f32 = 120.0;
+const POSTER_HEIGHT: f32 = 180.0;
+const PROGRESS_HEIGHT: f32 = 4.0;
 
 #[derive(Debug, Clone)]
 pub enum Msg {
     AddMedia,
     MenuButton(segmented_button::Entity),
     SortButton,
+    LayoutButton,
     SearchBarChanged(String),
+    Settings,
 }
 
 enum SortType {
@@ -39,14 +55,19 @@ pub struct MainPage {
     media_list_seg_button: SegButtonModel,
     sorting: Option<Sorting>,
     search_bar: String,
+    overviews: Overviews,
+    /// Show covers in a grid instead of the list
+    poster_grid: bool,
 }
 
 impl MainPage {
-    pub fn new(media_list: MediaListRef) -> Self {
+    pub fn new(media_list: MediaListRef, overviews: Overviews, poster_grid: bool) -> Self {
         Self {
             media_list_seg_button: Self::build(media_list),
             sorting: None,
             search_bar: String::new(),
+            overviews,
+            poster_grid,
         }
     }
 
@@ -54,6 +75,14 @@ impl MainPage {
         self.media_list_seg_button = Self::build(media_list);
     }
 
+    pub fn set_overview(&mut self, name: String, overview: Overview) {
+        self.overviews.insert(name, overview);
+    }
+
+    pub const fn set_poster_grid(&mut self, poster_grid: bool) {
+        self.poster_grid = poster_grid;
+    }
+
     pub fn update(&mut self, message: Msg, media_list: MediaListRefMut) -> Task<app::Msg> {
         match message {
             Msg::SortButton => {
@@ -96,7 +125,9 @@ impl MainPage {
                 }
                 self.media_list_seg_button = builder.build();
             }
+            Msg::LayoutButton => return Task::done(app::Msg::TogglePosterGrid),
             Msg::AddMedia => return Task::done(app::Msg::CreateMedia),
+            Msg::Settings => return Task::done(app::Msg::OpenSettings),
             Msg::MenuButton(entity) => {
                 let Some(selected_media_name) = self.media_list_seg_button.text(entity) else {
                     return Task::none();
@@ -114,12 +145,114 @@ impl MainPage {
         }
         builder.build()
     }
+
+    fn thumbnail(&self, name: &str) -> Option<&Path> {
+        self.overviews.get(name)?.thumbnail.as_deref()
+    }
+
+    /// Media of the list with covers and progress in the current chapter
+    fn list_view<'a>(&'a self, media_list: MediaListRef<'a>) -> Element<'a, Msg> {
+        let spacing = theme::spacing();
+        let media_by_name: HashMap<_, _> = media_list
+            .iter()
+            .map(|media| (media.name(), media))
+            .collect();
+        let rows = self.media_list_seg_button.iter().filter_map(|entity| {
+            let name = self.media_list_seg_button.text(entity)?;
+            let media = media_by_name.get(name)?;
+            let overview = self.overviews.get(name);
+            let last_episode = overview.and_then(|overview| overview.last_episode);
+            let position = match last_episode {
+                Some(last) => format!(
+                    "Chapter {}, episode {} of {last}",
+                    media.chapter(),
+                    media.episode()
+                ),
+                None => format!("Chapter {}, episode {}", media.chapter(), media.episode()),
+            };
+            let details = row![
+                text(position).font(font::light()).size(13),
+                horizontal_space(),
+                text(format!(
+                    "last watched {}",
+                    format_ago(media.changing_date())
+                ))
+                .font(font::light())
+                .size(13),
+            ]
+            .spacing(spacing.space_xs);
+            let progress = overview
+                .and_then(|overview| overview.progress(media.episode()))
+                .map(|progress| {
+                    progress_bar(0.0..=1.0, progress).height(Length::Fixed(PROGRESS_HEIGHT))
+                });
+            let description = Column::new()
+                .push(text::body(name))
+                .push(details)
+                .push_maybe(progress)
+                .spacing(spacing.space_xxs)
+                .width(Length::Fill);
+            let content = row![
+                cover(self.thumbnail(name), COVER_WIDTH, COVER_HEIGHT),
+                description
+            ]
+            .spacing(spacing.space_s)
+            .align_y(Alignment::Center);
+            Some(
+                button::custom(content)
+                    .class(style::Button::Text)
+                    .width(Length::Fill)
+                    .on_press(Msg::MenuButton(entity))
+                    .into(),
+            )
+        });
+        Column::with_children(rows)
+            .spacing(spacing.space_xxs)
+            .into()
+    }
+
+    /// Media of the list as buttons with covers
+    fn poster_grid_view(&self) -> Element<'_, Msg> {
+        let spacing = theme::spacing();
+        let posters = self
+            .media_list_seg_button
+            .iter()
+            .filter_map(|entity| {
+                let name = self.media_list_seg_button.text(entity)?;
+                let cover = cover(self.thumbnail(name), POSTER_WIDTH, POSTER_HEIGHT);
+                let poster = column![cover, text::body(name).width(POSTER_WIDTH)]
+                    .spacing(spacing.space_xxs)
+                    .align_x(Alignment::Center);
+                Some(
+                    button::custom(poster)
+                        .class(style::Button::Text)
+                        .on_press(Msg::MenuButton(entity))
+                        .into(),
+                )
+            })
+            .collect();
+        flex_row(posters)
+            .row_spacing(spacing.space_xs)
+            .column_spacing(spacing.space_xs)
+            .into()
+    }
 }
 
-impl Page for MainPage {
-    type Message = Msg;
+/// Thumbnail of the cover or an empty card of the same size
+fn cover<'a>(thumbnail: Option<&Path>, width: f32, height: f32) -> Element<'a, Msg> {
+    match thumbnail {
+        Some(thumbnail) => image(image::Handle::from_path(thumbnail))
+            .width(width)
+            .height(height)
+            .into(),
+        None => container(Space::new(width, height))
+            .class(style::Container::Card)
+            .into(),
+    }
+}
 
-    fn view(&self) -> Element<'_, Self::Message> {
+impl MainPage {
+    pub fn view<'a>(&'a self, media_list: MediaListRef<'a>) -> Element<'a, Msg> {
         let spacing = theme::spacing();
 
         column![
@@ -133,7 +266,16 @@ impl Page for MainPage {
                     .on_press(Msg::SortButton)
                 )
                 .width(Length::Fill),
-                button::suggested("Add media").on_press(Msg::AddMedia),
+                button::standard(if self.poster_grid { "List" } else { "Grid" })
+                    .on_press(Msg::LayoutButton),
+                row![
+                    Space::new(Length::Fixed(spacing.space_xs.into()), Length::Shrink),
+                    button::suggested("Add media").on_press(Msg::AddMedia),
+                ],
+                row![
+                    Space::new(Length::Fixed(spacing.space_xs.into()), Length::Shrink),
+                    button::standard("Settings").on_press(Msg::Settings),
+                ],
                 row![
                     Space::new(Length::Fixed(40.0), Length::Shrink),
                     search_bar(&self.search_bar).on_input(Msg::SearchBarChanged),
@@ -141,11 +283,11 @@ impl Page for MainPage {
             ])
             .width(Length::Fill)
             .align_x(Alignment::Center),
-            scrollable(
-                segmented_button::vertical(&self.media_list_seg_button)
-                    .on_activate(Msg::MenuButton)
-                    .button_padding([spacing.space_s, 0, 0, spacing.space_s])
-            )
+            scrollable(if self.poster_grid {
+                self.poster_grid_view()
+            } else {
+                self.list_view(media_list)
+            })
             .spacing(spacing.space_xxs)
             .height(Length::Fill),
         ]
@@ -7,11 +7,17 @@
 use std::path::PathBuf;
 
 use derive_more::Display;
+use open_media_tracker::PlayWarning;
 
 #[derive(Clone, Display)]
 pub enum ConfirmKind {
     #[display("Proposed path to next chapter: {path:?}")]
     SwitchToNextChapter { path: PathBuf },
+    #[display(
+        "Proposed path to previous chapter: {path:?}. \
+        Episode will be set to the last one in it"
+    )]
+    SwitchToPrevChapter { path: PathBuf },
     #[display(
         "Seems like {episodes_on_disk} episode is a last of it chapter. \
         Switch to the next chapter?"
@@ -25,6 +31,11 @@ impl ConfirmKind {
         Self::SwitchToNextChapter { path }
     }
 
+    pub fn switch_to_prev_chapter(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self::SwitchToPrevChapter { path }
+    }
+
     pub const fn episode_overflow(episodes_on_disk: usize) -> Self {
         Self::EpisodesOverflow { episodes_on_disk }
     }
@@ -36,4 +47,33 @@ pub enum WarningKind {
     NameUsed,
     #[display("Wrong chapter path")]
     WrongChapterPath,
+    #[display("Wrong series path")]
+    WrongSeriesPath,
+    #[display("Wrong cover path")]
+    WrongCoverPath,
+    #[display("File of the current episode \"{file_name}\" is not found in the chapter path")]
+    EpisodeFileMissing { file_name: String },
+    #[display("{message}")]
+    InvalidChapterPattern { message: String },
+    #[display("Failed to open the dialog: {message}")]
+    OpenDialog { message: String },
+    #[display("{_0}")]
+    Play(PlayWarning),
+}
+
+impl WarningKind {
+    pub fn episode_file_missing(file_name: impl Into<String>) -> Self {
+        let file_name = file_name.into();
+        Self::EpisodeFileMissing { file_name }
+    }
+
+    pub fn invalid_chapter_pattern(message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self::InvalidChapterPattern { message }
+    }
+
+    pub fn open_dialog(message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self::OpenDialog { message }
+    }
 }
@@ -9,25 +9,35 @@ use std::sync::Arc;
 
 use cosmic::dialog::file_chooser;
 use derive_more::From;
+use open_media_tracker::{Chapter, Episode, EpisodeList, Metadata, Result};
 use url::Url;
 
 use crate::gui::page::{ConfirmPageMsg, WarningPageMsg};
-use crate::model::{Episode, Result};
 
 #[derive(Debug, Clone, From)]
 pub enum Msg {
     Back,
     Delete(usize),
-    Watch {
-        path: PathBuf,
-    },
+    Watch(Episode),
+    Extracted(Episode, Result<PathBuf>),
     NameChanged(String),
     ChapterChanged(u8),
     EpisodeChanged(u8),
     ChapterPathChanged(String),
     ChapterPathSelect,
+    ChapterFileSelect,
     ChapterPathSelected(Url),
+    SeriesPathChanged(String),
+    SeriesPathSelect,
+    SeriesPathSelected(Url),
+    CoverChanged(String),
+    CoverSelect,
+    CoverSelected(Url),
     OpenChapterDirectory,
+    ChapterIncludeChanged(String),
+    ChapterExcludeChanged(String),
+    NaturalSortToggled(bool),
+    ToggleSkippedFiles,
     #[from]
     ConfirmScreen(ConfirmPageMsg),
     #[from]
@@ -35,16 +45,19 @@ pub enum Msg {
     OpenDialogCanceled,
     OpenDialogError(Arc<file_chooser::Error>),
     NextChapterPath(Result<PathBuf>),
-    EpisodeListLoaded(Result<Arc<Vec<Episode>>>),
+    PrevChapterPath(Result<PathBuf>),
+    EpisodeListLoaded(Result<Arc<EpisodeList>>),
+    FirstEpisodeListLoaded(Result<Arc<EpisodeList>>),
+    LastEpisodeListLoaded(Result<Arc<EpisodeList>>),
+    ChaptersLoaded(usize, Result<Arc<Vec<Chapter>>>),
+    MetadataLoaded {
+        chapter_path: PathBuf,
+        series_path: Option<PathBuf>,
+        metadata: Metadata,
+    },
     CheckOverflow {
         new_value: u8,
-        episode_list_read_res: Result<Arc<Vec<Episode>>>,
+        episode_list_read_res: Result<Arc<EpisodeList>>,
         if_not_then: Box<Msg>,
     },
 }
-
-impl Msg {
-    pub fn watch(path: impl Into<PathBuf>) -> Self {
-        Self::Watch { path: path.into() }
-    }
-}
@@ -17,50 +17,86 @@ use cosmic::iced::{Alignment, Length};
 use cosmic::iced_core::text::Wrapping;
 use cosmic::iced_widget::{column, row};
 use cosmic::widget::{
-    Column, Space, button, container, divider, horizontal_space, popover, text, tooltip,
+    Column, Row, Space, button, container, divider, horizontal_space, image, popover, text,
+    toggler, tooltip,
 };
 use cosmic::{Element, Task, font, style, theme};
 use expand_tilde::ExpandTilde;
+use open_media_tracker::utils::{self, format_duration};
+use open_media_tracker::{
+    Chapter, ChapterFilter, Config, Episode, ErrorKind, MediaHandler, MediaList, MediaListRef,
+    MediaListRefMut, Metadata, Reconciliation, Result, Settings, SidecarKind, chapter_number,
+    extract, is_archive, is_playlist,
+};
+use url::Url;
 
 use crate::gui;
 use crate::gui::page::{ConfirmDlg, ConfirmPageMsg, WarningDlg, WarningPageMsg};
-use crate::gui::utils::signed_text_input;
-use crate::model::{
-    Episode, Episodes, ErrorKind, LoadedData, MediaHandler, MediaList, MediaListRef,
-    MediaListRefMut, Result,
-};
-use crate::utils;
+use crate::gui::utils::{join_patterns, signed_text_input, split_patterns};
+use crate::gui::{Episodes, LoadedData};
 use kind::{ConfirmKind, WarningKind};
 pub use message::Msg;
 
+const POSTER_WIDTH: f32 = 120.0;
+
 pub struct MediaEditPage {
     confirm: ConfirmDlg<ConfirmKind>,
     warning: WarningDlg<WarningKind>,
     editable_media_id: usize,
     episodes: Episodes,
+    chapters: LoadedData<Arc<Vec<Chapter>>, ErrorKind>,
+    /// Number of the latest chapters scan, results of older scans are dropped
+    chapters_scan: usize,
     buffer_name: String,
+    chapter_include: String,
+    chapter_exclude: String,
+    settings: Arc<Settings>,
+    config: Arc<Config>,
     chapter: u8,
     episode: u8,
+    show_skipped: bool,
+    extracting: bool,
 }
 
 impl MediaEditPage {
-    pub fn new(media_list: MediaListRef, editable_media_id: usize) -> (Self, Task<Msg>) {
+    pub fn new(
+        media_list: MediaListRef,
+        editable_media_id: usize,
+        settings: Arc<Settings>,
+        config: Arc<Config>,
+    ) -> (Self, Task<Msg>) {
         let editable_media = &media_list[editable_media_id];
-        let task = load_episodes(editable_media);
+        let task = Task::batch([
+            load_episodes(editable_media, &settings),
+            load_chapters(editable_media, &settings, 0),
+        ]);
+        let chapter_filter = editable_media.chapter_filter();
         (
             Self {
                 confirm: ConfirmDlg::closed(),
                 warning: WarningDlg::closed(),
                 editable_media_id,
                 episodes: LoadedData::Loading.into(),
+                chapters: LoadedData::Loading,
+                chapters_scan: 0,
                 buffer_name: editable_media.name().to_string(),
+                chapter_include: join_patterns(&chapter_filter.include),
+                chapter_exclude: join_patterns(&chapter_filter.exclude),
+                settings,
+                config,
                 chapter: editable_media.chapter(),
                 episode: editable_media.episode(),
+                show_skipped: false,
+                extracting: false,
             },
             task,
         )
     }
 
+    pub const fn media_id(&self) -> usize {
+        self.editable_media_id
+    }
+
     pub fn view<'a>(&'a self, media_list: &'a [MediaHandler]) -> Element<'a, Msg> {
         let spacing = theme::spacing();
 
@@ -79,29 +115,35 @@ impl MediaEditPage {
         ]
         .align_y(Alignment::Center);
         let watch = container(
-            button::suggested("Watch").on_press_maybe(
+            button::suggested(if self.extracting {
+                "Extracting..."
+            } else {
+                "Watch"
+            })
+            .on_press_maybe(
                 self.episode(media_list)
-                    .and_then(|res| res.ok().map(Episode::path).map(Msg::watch)),
+                    .filter(|_| !self.extracting)
+                    .and_then(|res| res.ok().cloned().map(Msg::Watch)),
             ),
         )
         .width(Length::Fill)
         .align_x(Alignment::Center);
-        let watch_sign = self.watch_sign(media_list).map(|watch_sign| {
-            text(watch_sign)
-                .font(font::light())
-                .size(13)
-                .wrapping(Wrapping::WordOrGlyph)
-                .align_x(Alignment::Center)
-                .width(Length::Fill)
-        });
-        let edit_view = self.edit_view(media.chapter_path());
+        let watch_sign = self.watch_sign(media_list).map(sign_text);
+        let sidecars_sign = self.sidecars_sign(media_list).map(sign_text);
+        let remaining_sign = self.remaining_sign().map(sign_text);
+        let metadata_view = metadata_view(media.metadata(), media.cover());
+        let edit_view = self.edit_view(media);
 
         let layout = Column::new()
             .push(top)
             .push(Space::with_height(Length::Fixed(spacing.space_xxs.into())))
             .push(watch)
             .push_maybe(watch_sign)
+            .push_maybe(sidecars_sign)
+            .push_maybe(remaining_sign)
+            .push_maybe(self.skipped_files_view())
             .push_maybe(self.warning.view_into())
+            .push_maybe(metadata_view)
             .push(edit_view)
             .padding(spacing.space_xs)
             .spacing(spacing.space_xs)
@@ -116,20 +158,47 @@ impl MediaEditPage {
         layout.into()
     }
 
-    fn edit_view<'a>(&'a self, chapter_path: &'a Path) -> Element<'a, Msg> {
+    fn skipped_files_view(&self) -> Option<Element<'_, Msg>> {
+        let skipped = self.episodes.as_option()?.skipped();
+        if skipped.is_empty() {
+            return None;
+        }
+        let toggle = button::text(format!("{} files skipped", skipped.len()))
+            .on_press(Msg::ToggleSkippedFiles);
+        let mut layout = Column::new().push(toggle);
+        if self.show_skipped {
+            for file in skipped {
+                layout = layout.push(text(file.to_string()).font(font::light()).size(13));
+            }
+        }
+        Some(
+            container(layout.spacing(theme::spacing().space_xxs))
+                .width(Length::Fill)
+                .align_x(Alignment::Center)
+                .into(),
+        )
+    }
+
+    fn edit_view<'a>(&'a self, media: &'a MediaHandler) -> Element<'a, Msg> {
         let spacing = theme::spacing();
+        let chapter_path = media.chapter_path();
+        let series_path = media.series_path().unwrap_or_else(|| Path::new(""));
+        let cover = media.cover().unwrap_or_else(|| Path::new(""));
 
         container(
             column![
                 signed_text_input("Name", &self.buffer_name, Msg::NameChanged),
                 divider::horizontal::default(),
-                row![
-                    "Chapter",
-                    horizontal_space(),
-                    gui::utils::spin_button(self.chapter, Msg::ChapterChanged),
-                ]
-                .spacing(spacing.space_xs)
-                .align_y(Alignment::Center),
+                Row::new()
+                    .push(text("Chapter"))
+                    .push(horizontal_space())
+                    .push_maybe(
+                        self.chapter_sign()
+                            .map(|sign| text(sign).font(font::light()))
+                    )
+                    .push(gui::utils::spin_button(self.chapter, Msg::ChapterChanged))
+                    .spacing(spacing.space_xs)
+                    .align_y(Alignment::Center),
                 divider::horizontal::default(),
                 row![
                     "Episode",
@@ -139,6 +208,21 @@ impl MediaEditPage {
                 .spacing(spacing.space_xxs)
                 .align_y(Alignment::Center),
                 divider::horizontal::default(),
+                row![
+                    signed_text_input(
+                        "Series path",
+                        series_path.to_string_lossy(),
+                        Msg::SeriesPathChanged
+                    ),
+                    tooltip(
+                        select_button("...", Msg::SeriesPathSelect),
+                        text("Select folder"),
+                        tooltip::Position::Top
+                    ),
+                ]
+                .align_y(Alignment::Center)
+                .spacing(spacing.space_xs),
+                divider::horizontal::default(),
                 row![
                     signed_text_input(
                         "Chapter path",
@@ -146,14 +230,15 @@ impl MediaEditPage {
                         Msg::ChapterPathChanged
                     ),
                     tooltip(
-                        button::standard("...")
-                            .height(30)
-                            .font_size(18)
-                            .font_weight(Weight::Bold)
-                            .on_press(Msg::ChapterPathSelect),
+                        select_button("...", Msg::ChapterPathSelect),
                         text("Select folder"),
                         tooltip::Position::Top
                     ),
+                    tooltip(
+                        select_button("File", Msg::ChapterFileSelect),
+                        text("Select playlist or ZIP archive"),
+                        tooltip::Position::Top
+                    ),
                     button::standard("")
                         .leading_icon(gui::icon::folder())
                         .height(30)
@@ -164,7 +249,40 @@ impl MediaEditPage {
                         ),
                 ]
                 .align_y(Alignment::Center)
+                .spacing(spacing.space_xs),
+                divider::horizontal::default(),
+                row![
+                    signed_text_input("Cover", cover.to_string_lossy(), Msg::CoverChanged),
+                    tooltip(
+                        select_button("...", Msg::CoverSelect),
+                        text("Select image"),
+                        tooltip::Position::Top
+                    ),
+                ]
+                .align_y(Alignment::Center)
+                .spacing(spacing.space_xs),
+                divider::horizontal::default(),
+                row![
+                    signed_text_input(
+                        "Include chapters",
+                        &self.chapter_include,
+                        Msg::ChapterIncludeChanged
+                    ),
+                    signed_text_input(
+                        "Exclude chapters",
+                        &self.chapter_exclude,
+                        Msg::ChapterExcludeChanged
+                    ),
+                ]
+                .spacing(spacing.space_xs),
+                divider::horizontal::default(),
+                row![
+                    "Natural sorting",
+                    horizontal_space(),
+                    toggler(media.sort_order().is_natural()).on_toggle(Msg::NaturalSortToggled),
+                ]
                 .spacing(spacing.space_xs)
+                .align_y(Alignment::Center),
             ]
             .spacing(spacing.space_xs),
         )
@@ -190,24 +308,65 @@ impl MediaEditPage {
                     self.warning.close();
                 }
             }
-            Msg::ChapterChanged(value) => {
-                self.chapter = value;
-                self.editable_media_mut(media_list).set_chapter(value)?;
-            }
+            Msg::ChapterChanged(value) => return self.set_chapter(media_list, value),
             Msg::EpisodeChanged(value) => return self.set_episode(media_list, value),
             Msg::ChapterPathChanged(value) => {
                 return self.set_chapter_path(media_list, value);
             }
+            Msg::SeriesPathChanged(value) => {
+                let series_path = (!value.is_empty()).then(|| PathBuf::from(value));
+                return self.set_series_path(media_list, series_path);
+            }
+            Msg::ChapterIncludeChanged(value) => {
+                self.chapter_include = value;
+                return self.set_chapter_filter(media_list);
+            }
+            Msg::ChapterExcludeChanged(value) => {
+                self.chapter_exclude = value;
+                return self.set_chapter_filter(media_list);
+            }
+            Msg::ToggleSkippedFiles => self.show_skipped = !self.show_skipped,
+            Msg::NaturalSortToggled(natural) => {
+                let media = self.editable_media_mut(media_list);
+                media.set_sort_order(natural.into())?;
+                return Ok(Task::batch([
+                    load_episodes(media, &self.settings),
+                    self.load_chapters(media),
+                ]));
+            }
             Msg::ConfirmScreen(message) => return self.confirm_screen_update(media_list, &message),
             Msg::ChapterPathSelect => {
-                return Ok(Task::future(async {
-                    let dialog = file_chooser::open::Dialog::new().title("Select chapter path");
-                    match dialog.open_folder().await {
-                        Ok(responce) => Msg::ChapterPathSelected(responce.url().to_owned()),
-                        Err(file_chooser::Error::Cancelled) => Msg::OpenDialogCanceled,
-                        Err(err) => Msg::OpenDialogError(Arc::new(err)),
-                    }
-                }));
+                return Ok(select_folder(
+                    "Select chapter path",
+                    Msg::ChapterPathSelected,
+                ));
+            }
+            Msg::ChapterFileSelect => {
+                let filter = file_chooser::FileFilter::new("Playlists and archives")
+                    .glob("*.m3u")
+                    .glob("*.m3u8")
+                    .glob("*.pls")
+                    .glob("*.xspf")
+                    .glob("*.zip");
+                return Ok(select_file(
+                    "Select playlist or archive",
+                    filter,
+                    Msg::ChapterPathSelected,
+                ));
+            }
+            Msg::SeriesPathSelect => {
+                return Ok(select_folder("Select series path", Msg::SeriesPathSelected));
+            }
+            Msg::CoverChanged(value) => {
+                let cover = (!value.is_empty()).then(|| PathBuf::from(value));
+                self.editable_media_mut(media_list).set_cover(cover)?;
+            }
+            Msg::CoverSelect => {
+                let filter = file_chooser::FileFilter::new("Images")
+                    .glob("*.jpg")
+                    .glob("*.jpeg")
+                    .glob("*.png");
+                return Ok(select_file("Select cover", filter, Msg::CoverSelected));
             }
             Msg::Warning(WarningPageMsg::Close) => self.warning.close(),
             Msg::OpenChapterDirectory => {
@@ -215,21 +374,75 @@ impl MediaEditPage {
                     .editable_media(media_list)
                     .chapter_path()
                     .expand_tilde()?;
-                if !chapter_path.is_dir() {
+                let directory = if is_playlist(&chapter_path) || is_archive(&chapter_path) {
+                    chapter_path.parent().unwrap_or(&chapter_path)
+                } else {
+                    &chapter_path
+                };
+                if !directory.is_dir() {
                     self.warning(WarningKind::WrongChapterPath);
                     return Ok(Task::none());
                 }
-                utils::open(&*chapter_path)?;
+                utils::open(directory)?;
             }
             Msg::ChapterPathSelected(url) => {
                 if let Ok(path) = url.to_file_path() {
-                    return self.set_chapter_path(media_list, path);
+                    let task = self.set_chapter_path(media_list, path)?;
+                    let metadata = load_metadata(self.editable_media(media_list));
+                    return Ok(Task::batch([task, metadata]));
                 }
                 self.warning(WarningKind::WrongChapterPath);
             }
-            Msg::OpenDialogError(err) => return Err(ErrorKind::open_dialog(err)),
+            Msg::SeriesPathSelected(url) => {
+                if let Ok(path) = url.to_file_path() {
+                    let task = self.set_series_path(media_list, Some(path))?;
+                    let metadata = load_metadata(self.editable_media(media_list));
+                    return Ok(Task::batch([task, metadata]));
+                }
+                self.warning(WarningKind::WrongSeriesPath);
+            }
+            Msg::CoverSelected(url) => {
+                if let Ok(path) = url.to_file_path() {
+                    self.editable_media_mut(media_list).set_cover(Some(path))?;
+                } else {
+                    self.warning(WarningKind::WrongCoverPath);
+                }
+            }
+            Msg::OpenDialogError(err) => self.warning(WarningKind::open_dialog(err.to_string())),
             Msg::NextChapterPath(path) => self.confirm_switch_to_next_chapter(path?),
-            Msg::EpisodeListLoaded(res) => self.episodes = Episodes(res.into()),
+            // Decreasing the first chapter is not an error
+            Msg::PrevChapterPath(Err(ErrorKind::FindPrevChapterPath { .. })) => {}
+            Msg::PrevChapterPath(path) => self.confirm_switch_to_prev_chapter(path?),
+            Msg::EpisodeListLoaded(res) => {
+                self.episodes = Episodes(res.into());
+                self.reconcile_episode(media_list)?;
+            }
+            Msg::FirstEpisodeListLoaded(res) => {
+                self.episodes = Episodes(res.into());
+                self.set_first_episode(media_list)?;
+            }
+            Msg::LastEpisodeListLoaded(res) => {
+                self.episodes = Episodes(res.into());
+                self.set_last_episode(media_list)?;
+            }
+            Msg::ChaptersLoaded(scan, _) if scan != self.chapters_scan => {}
+            Msg::ChaptersLoaded(_, res) => {
+                self.chapters = res.into();
+                return self.reconcile_chapter(media_list);
+            }
+            Msg::MetadataLoaded {
+                chapter_path,
+                series_path,
+                metadata,
+            } => {
+                let media = self.editable_media_mut(media_list);
+                // Metadata of paths changed meanwhile is outdated
+                if media.chapter_path() == chapter_path
+                    && media.series_path() == series_path.as_deref()
+                {
+                    media.set_metadata(metadata)?;
+                }
+            }
             Msg::CheckOverflow {
                 new_value,
                 episode_list_read_res,
@@ -239,12 +452,16 @@ impl MediaEditPage {
                 if !self.is_episode_overflow(new_value) {
                     return Ok(Task::done(*if_not_then));
                 }
-                let Some(episodes_count) = self.episodes.len() else {
+                let Some(episodes_count) = self.episodes.last_number() else {
                     return Ok(Task::none());
                 };
                 self.confirm_episode_overflow(episodes_count);
             }
-            Msg::Watch { path } => utils::open(path)?,
+            Msg::Watch(episode) => return self.watch(episode),
+            Msg::Extracted(episode, path) => {
+                self.extracting = false;
+                self.play(&episode.extracted(path?))?;
+            }
             _ => {}
         }
         Ok(Task::none())
@@ -262,14 +479,64 @@ impl MediaEditPage {
         if self.episodes.is_loading() {
             return Some("Loading...".into());
         }
-        let watch_sign = match self.episodes.get(self.episode_id(media_list))? {
-            Ok(episode) => episode.name(),
+        let watch_sign = match self.episode(media_list)? {
+            Ok(episode) => match episode.number() {
+                Some(number) => format!("{} ({number})", episode.name()).into(),
+                None => episode.name(),
+            },
             Err(ErrorKind::Io(err)) => format!("Chapter path is incorrect: {err}").into(),
             Err(err) => format!("Chapter path is incorrect: {err}").into(),
         };
         Some(watch_sign)
     }
 
+    /// Subtitles and audio tracks which will be passed to the player
+    fn sidecars_sign(&self, media_list: MediaListRef) -> Option<String> {
+        let Some(Ok(episode)) = self.episode(media_list) else {
+            return None;
+        };
+        let file_names = |kind| {
+            episode
+                .sidecars()
+                .iter()
+                .filter(|sidecar| sidecar.kind() == kind)
+                .map(|sidecar| sidecar.file_name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let subtitles = file_names(SidecarKind::Subtitle);
+        let audio = file_names(SidecarKind::Audio);
+        let sign = [("Subtitles", subtitles), ("Audio", audio)]
+            .into_iter()
+            .filter(|(_, file_names)| !file_names.is_empty())
+            .map(|(title, file_names)| format!("{title}: {file_names}"))
+            .collect::<Vec<_>>()
+            .join("  ·  ");
+        (!sign.is_empty()).then_some(sign)
+    }
+
+    /// Time left to watch in the chapter and in the whole series
+    fn remaining_sign(&self) -> Option<String> {
+        let chapter_left = self.episodes.remaining_duration(self.episode)?;
+        let chapter_sign = format!("{} left in chapter", format_duration(chapter_left));
+        let later_chapters = self
+            .chapters
+            .as_option()
+            .filter(|_| self.chapter(self.chapter).is_some())
+            .map(|chapters| &chapters[usize::from(self.chapter)..]);
+        let Some(later_chapters) = later_chapters else {
+            return Some(chapter_sign);
+        };
+        let series_left = later_chapters
+            .iter()
+            .filter_map(Chapter::duration)
+            .fold(chapter_left, |total, duration| total + duration);
+        Some(format!(
+            "{chapter_sign}  ·  {} left in series",
+            format_duration(series_left)
+        ))
+    }
+
     fn confirm_screen_update(
         &mut self,
         media_list: MediaListRefMut,
@@ -288,7 +555,12 @@ impl MediaEditPage {
         };
         self.confirm.close();
         match kind {
-            ConfirmKind::SwitchToNextChapter { path } => self.set_chapter_path(media_list, path),
+            ConfirmKind::SwitchToNextChapter { path } => {
+                self.set_chapter_path_from_start(media_list, path)
+            }
+            ConfirmKind::SwitchToPrevChapter { path } => {
+                self.set_chapter_path_from_end(media_list, path)
+            }
             ConfirmKind::EpisodesOverflow { .. } => self.increase_chapter(media_list),
         }
     }
@@ -305,21 +577,209 @@ impl MediaEditPage {
         &self,
         media_list: MediaListRef,
     ) -> Option<std::result::Result<&Episode, &ErrorKind>> {
-        self.episodes.get(self.episode_id(media_list))
+        self.episodes
+            .find(self.editable_media(media_list).episode())
     }
 
-    const fn episode_id(&self, media_list: MediaListRef) -> usize {
-        (self.editable_media(media_list).episode() - 1) as usize
+    fn set_chapter_path(
+        &self,
+        media_list: MediaListRefMut,
+        chapter_path: impl Into<PathBuf>,
+    ) -> Result<Task<Msg>> {
+        let editable_media = self.editable_media_mut(media_list);
+        editable_media.set_chapter_path(chapter_path)?;
+        Ok(load_episodes(editable_media, &self.settings))
     }
 
-    fn set_chapter_path(
+    /// Correct the episode number if the remembered episode file has moved
+    fn reconcile_episode(&mut self, media_list: MediaListRefMut) -> Result<()> {
+        let Some(episodes) = self.episodes.as_option().cloned() else {
+            return Ok(());
+        };
+        let media = self.editable_media_mut(media_list);
+        match media.reconcile_episode(&episodes)? {
+            Reconciliation::Unchanged => {}
+            Reconciliation::Moved { to, .. } => self.episode = to,
+            Reconciliation::Missing { file_name } => {
+                self.warning(WarningKind::episode_file_missing(file_name));
+                return Ok(());
+            }
+        }
+        self.anchor_episode(media_list)
+    }
+
+    fn anchor_episode(&self, media_list: MediaListRefMut) -> Result<()> {
+        let media = self.editable_media_mut(media_list);
+        if let Some(Ok(episode)) = self.episodes.find(media.episode()) {
+            media.set_episode_anchor(episode)?;
+        }
+        Ok(())
+    }
+
+    fn chapter(&self, number: u8) -> Option<&Chapter> {
+        let chapters = self.chapters.as_option()?;
+        chapters.get(usize::from(number).checked_sub(1)?)
+    }
+
+    fn chapter_sign(&self) -> Option<String> {
+        let chapter = self.chapter(self.chapter)?;
+        Some(format!(
+            "{} ({} episodes)",
+            chapter.name(),
+            chapter.episodes_count()
+        ))
+    }
+
+    fn watch(&mut self, episode: Episode) -> Result<Task<Msg>> {
+        let Some(entry) = episode.archive_entry().cloned() else {
+            self.play(&episode)?;
+            return Ok(Task::none());
+        };
+        self.extracting = true;
+        let cache_dir = self.config.archive_cache.clone();
+        let limits = self.settings.archive_limits.clone();
+        Ok(Task::future(async move {
+            Msg::Extracted(episode, extract(entry, cache_dir, limits).await)
+        }))
+    }
+
+    fn play(&mut self, episode: &Episode) -> Result<()> {
+        if let Some(warning) = self.settings.player.play(episode)? {
+            self.warning(WarningKind::Play(warning));
+        }
+        Ok(())
+    }
+
+    fn set_chapter(&mut self, media_list: MediaListRefMut, value: u8) -> Result<Task<Msg>> {
+        let decreased = value < self.chapter;
+        self.chapter = value;
+        let media = self.editable_media_mut(media_list);
+        media.set_chapter(value)?;
+        let Some(chapter_path) = self.chapter(value).map(|c| c.path().to_path_buf()) else {
+            if decreased && !media.chapter_path().as_os_str().is_empty() {
+                let prev_chapter_path = media.prev_chapter_path(&self.settings);
+                return Ok(Task::future(async {
+                    Msg::PrevChapterPath(prev_chapter_path.await)
+                }));
+            }
+            return Ok(Task::none());
+        };
+        if chapter_path == media.chapter_path() {
+            return Ok(Task::none());
+        }
+        if decreased {
+            return self.set_chapter_path_from_end(media_list, chapter_path);
+        }
+        self.set_chapter_path_from_start(media_list, chapter_path)
+    }
+
+    fn set_series_path(
+        &mut self,
+        media_list: MediaListRefMut,
+        series_path: Option<PathBuf>,
+    ) -> Result<Task<Msg>> {
+        self.chapters = LoadedData::Loading;
+        let media = self.editable_media_mut(media_list);
+        media.set_series_path(series_path)?;
+        Ok(self.load_chapters(media))
+    }
+
+    fn set_chapter_filter(&mut self, media_list: MediaListRefMut) -> Result<Task<Msg>> {
+        let chapter_filter = ChapterFilter {
+            include: split_patterns(&self.chapter_include),
+            exclude: split_patterns(&self.chapter_exclude),
+        };
+        if let Err(err) = chapter_filter.validate() {
+            self.warning(WarningKind::invalid_chapter_pattern(err.to_string()));
+            return Ok(Task::none());
+        }
+        if matches!(
+            self.warning.kind(),
+            Some(WarningKind::InvalidChapterPattern { .. })
+        ) {
+            self.warning.close();
+        }
+        let media = self.editable_media_mut(media_list);
+        if media.chapter_filter() == &chapter_filter {
+            return Ok(Task::none());
+        }
+        media.set_chapter_filter(chapter_filter)?;
+        Ok(self.load_chapters(media))
+    }
+
+    /// Bring chapter number and chapter path in accordance with the series
+    fn reconcile_chapter(&mut self, media_list: MediaListRefMut) -> Result<Task<Msg>> {
+        let Some(chapters) = self.chapters.as_option().cloned() else {
+            return Ok(Task::none());
+        };
+        if chapters.is_empty() {
+            return Ok(Task::none());
+        }
+        let media = self.editable_media_mut(media_list);
+        if media.chapter_path().as_os_str().is_empty() {
+            return self.set_chapter(media_list, self.chapter);
+        }
+        if let Some(number) = chapter_number(&chapters, media.chapter_path())
+            && number != self.chapter
+        {
+            self.chapter = number;
+            media.set_chapter(number)?;
+        }
+        Ok(Task::none())
+    }
+
+    /// Set chapter path and move to the first episode in it
+    fn set_chapter_path_from_start(
+        &self,
+        media_list: MediaListRefMut,
+        chapter_path: impl Into<PathBuf>,
+    ) -> Result<Task<Msg>> {
+        let editable_media = self.editable_media_mut(media_list);
+        editable_media.set_chapter_path(chapter_path)?;
+        let future = editable_media.episode_list(&self.settings);
+        Ok(Task::batch([
+            Task::future(async { Msg::FirstEpisodeListLoaded(future.await.map(Arc::new)) }),
+            load_metadata(editable_media),
+        ]))
+    }
+
+    fn set_first_episode(&mut self, media_list: MediaListRefMut) -> Result<()> {
+        let first_episode = self.episodes.first_number().unwrap_or(1);
+        self.episode = first_episode;
+        self.editable_media_mut(media_list)
+            .set_episode(first_episode)?;
+        self.anchor_episode(media_list)
+    }
+
+    /// Set chapter path and move to the last episode in it
+    fn set_chapter_path_from_end(
         &self,
         media_list: MediaListRefMut,
         chapter_path: impl Into<PathBuf>,
     ) -> Result<Task<Msg>> {
         let editable_media = self.editable_media_mut(media_list);
         editable_media.set_chapter_path(chapter_path)?;
-        Ok(load_episodes(editable_media))
+        let future = editable_media.episode_list(&self.settings);
+        Ok(Task::batch([
+            Task::future(async { Msg::LastEpisodeListLoaded(future.await.map(Arc::new)) }),
+            load_metadata(editable_media),
+        ]))
+    }
+
+    fn set_last_episode(&mut self, media_list: MediaListRefMut) -> Result<()> {
+        let Some(last_episode) = self.episodes.last_number() else {
+            return Ok(());
+        };
+        let last_episode = u8::try_from(last_episode).unwrap_or(u8::MAX);
+        self.episode = last_episode;
+        self.editable_media_mut(media_list)
+            .set_episode(last_episode)?;
+        self.anchor_episode(media_list)
+    }
+
+    fn load_chapters(&mut self, media: &MediaHandler) -> Task<Msg> {
+        self.chapters_scan += 1;
+        load_chapters(media, &self.settings, self.chapters_scan)
     }
 
     fn warning(&mut self, kind: WarningKind) {
@@ -327,16 +787,19 @@ impl MediaEditPage {
     }
 
     fn is_episode_overflow(&self, value: u8) -> bool {
-        self.episodes.len().is_some_and(|ec| ec < value as usize)
+        self.episodes
+            .last_number()
+            .is_some_and(|ec| ec < value as usize)
     }
 
     fn set_episode(&mut self, media_list: MediaListRefMut, value: u8) -> Result<Task<Msg>> {
         let media = self.editable_media_mut(media_list);
 
-        match self.episodes.len() {
+        match self.episodes.last_number() {
             Some(episodes_count) if value as usize <= episodes_count => {
                 self.episode = value;
                 media.set_episode(value)?;
+                self.anchor_episode(media_list)?;
             }
             None => {
                 self.episode = value;
@@ -360,7 +823,7 @@ impl MediaEditPage {
     ) -> Task<Msg> {
         self.episodes = Episodes(LoadedData::Loading);
         let media = self.editable_media(media_list);
-        let future = media.episode_list();
+        let future = media.episode_list(&self.settings);
         Task::future(async move {
             Msg::CheckOverflow {
                 new_value,
@@ -376,16 +839,21 @@ impl MediaEditPage {
             return Ok(Task::none());
         }
 
-        self.episode = 1;
         let media = self.editable_media_mut(media_list);
-        media.set_episode(1)?;
         let next_chapter = media.chapter().saturating_add(1);
         self.chapter = next_chapter;
         media.set_chapter(next_chapter)?;
+        if let Some(chapter) = self.chapter(next_chapter) {
+            let chapter_path = chapter.path().to_path_buf();
+            return self.set_chapter_path_from_start(media_list, chapter_path);
+        }
+        // Media without episodes counts them by position
         if media.chapter_path().as_os_str().is_empty() {
+            self.episode = 1;
+            media.set_episode(1)?;
             return Ok(Task::none());
         }
-        let next_chapter_path = media.next_chapter_path();
+        let next_chapter_path = media.next_chapter_path(&self.settings);
         Ok(Task::future(async {
             Msg::NextChapterPath(next_chapter_path.await)
         }))
@@ -400,13 +868,124 @@ impl MediaEditPage {
         self.confirm(kind);
     }
 
+    fn confirm_switch_to_prev_chapter(&mut self, prev_chapter_path: impl Into<PathBuf>) {
+        let kind = ConfirmKind::switch_to_prev_chapter(prev_chapter_path);
+        self.confirm(kind);
+    }
+
     fn confirm_episode_overflow(&mut self, episodes_count: usize) {
         let kind = ConfirmKind::episode_overflow(episodes_count);
         self.confirm(kind);
     }
 }
 
-fn load_episodes(media: &MediaHandler) -> Task<Msg> {
-    let future = media.episode_list();
+fn load_episodes(media: &MediaHandler, settings: &Settings) -> Task<Msg> {
+    let future = media.episode_list(settings);
     Task::future(async { Msg::EpisodeListLoaded(future.await.map(Arc::new)) })
 }
+
+fn load_chapters(media: &MediaHandler, settings: &Settings, scan: usize) -> Task<Msg> {
+    let future = media.chapter_list(settings);
+    Task::future(async move { Msg::ChaptersLoaded(scan, future.await.map(Arc::new)) })
+}
+
+/// Metadata is read once the paths are selected or switched, typed paths
+/// get it when the page is left
+fn load_metadata(media: &MediaHandler) -> Task<Msg> {
+    let chapter_path = media.chapter_path().to_path_buf();
+    let series_path = media.series_path().map(Path::to_path_buf);
+    let future = media.load_metadata();
+    Task::future(async move {
+        Msg::MetadataLoaded {
+            chapter_path,
+            series_path,
+            metadata: future.await,
+        }
+    })
+}
+
+/// Poster, title and plot found next to the chapter. The cover chosen
+/// manually replaces the poster
+fn metadata_view<'a>(metadata: &'a Metadata, cover: Option<&'a Path>) -> Option<Element<'a, Msg>> {
+    if metadata.is_empty() && cover.is_none() {
+        return None;
+    }
+    let spacing = theme::spacing();
+    let title = match (&metadata.title, metadata.year) {
+        (Some(title), Some(year)) => Some(format!("{title} ({year})")),
+        (Some(title), None) => Some(title.clone()),
+        (None, Some(year)) => Some(year.to_string()),
+        (None, None) => None,
+    };
+    let details = Column::new()
+        .push_maybe(title.map(text::heading))
+        .push_maybe(
+            metadata
+                .plot
+                .as_deref()
+                .map(|plot| text(plot).font(font::light()).size(13)),
+        )
+        .spacing(spacing.space_xxs)
+        .width(Length::Fill);
+    let poster = cover
+        .or(metadata.poster.as_deref())
+        .map(|poster| image(image::Handle::from_path(poster)).width(POSTER_WIDTH));
+    Some(
+        container(
+            Row::new()
+                .push_maybe(poster)
+                .push(details)
+                .spacing(spacing.space_xs),
+        )
+        .padding(spacing.space_xs)
+        .class(style::Container::Card)
+        .into(),
+    )
+}
+
+fn sign_text<'a>(content: impl Into<Cow<'a, str>>) -> Element<'a, Msg> {
+    text(content)
+        .font(font::light())
+        .size(13)
+        .wrapping(Wrapping::WordOrGlyph)
+        .align_x(Alignment::Center)
+        .width(Length::Fill)
+        .into()
+}
+
+fn select_folder(title: &'static str, on_select: fn(Url) -> Msg) -> Task<Msg> {
+    Task::future(async move {
+        let dialog = file_chooser::open::Dialog::new().title(title);
+        match dialog.open_folder().await {
+            Ok(responce) => on_select(responce.url().to_owned()),
+            Err(file_chooser::Error::Cancelled) => Msg::OpenDialogCanceled,
+            Err(err) => Msg::OpenDialogError(Arc::new(err)),
+        }
+    })
+}
+
+fn select_file(
+    title: &'static str,
+    filter: file_chooser::FileFilter,
+    on_select: fn(Url) -> Msg,
+) -> Task<Msg> {
+    Task::future(async move {
+        let dialog = file_chooser::open::Dialog::new()
+            .title(title)
+            .filter(filter);
+        match dialog.open_file().await {
+            Ok(response) => on_select(response.url().to_owned()),
+            Err(file_chooser::Error::Cancelled) => Msg::OpenDialogCanceled,
+            Err(err) => Msg::OpenDialogError(Arc::new(err)),
+        }
+    })
+}
+
+fn select_button<'a>(label: &'a str, on_press: Msg) -> Element<'a, Msg> {
+    button::standard(label)
+        .height(30)
+        .font_size(18)
+        .font_weight(Weight::Bold)
+        .on_press(on_press)
+        .into()
+}
@@ -9,6 +9,7 @@ pub mod error;
 pub mod loading;
 pub mod main;
 pub mod media_edit;
+pub mod settings;
 pub mod warning;
 
 pub use confirm::{ConfirmDlg, Msg as ConfirmPageMsg};
@@ -16,6 +17,7 @@ pub use error::{ErrorPage, Msg as ErrorPageMsg};
 pub use loading::{LoadingPage, Msg as LoadingPageMsg};
 pub use main::{MainPage, Msg as MainPageMsg};
 pub use media_edit::{MediaEditPage, Msg as MediaEditPageMsg};
+pub use settings::{Msg as SettingsPageMsg, SettingsPage};
 pub use warning::{Msg as WarningPageMsg, WarningDlg};
 
 use cosmic::Element;
@@ -0,0 +1,339 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use cosmic::iced::{Alignment, Length};
+use cosmic::iced_widget::row;
+use cosmic::widget::{
+    Column, button, container, divider, horizontal_space, scrollable, text, toggler,
+};
+use cosmic::{Element, font, style, theme};
+use open_media_tracker::{
+    ArchiveLimits, ChapterFilter, ErrorKind, MediaExtensions, Player, RemoteApi, Settings,
+};
+
+use crate::error::AppError;
+use crate::gui::utils::{join_patterns, signed_text_input, split_patterns};
+use crate::gui::{self, Page};
+
+#[derive(Debug, Clone)]
+pub enum Msg {
+    Back,
+    ChapterIncludeChanged(String),
+    ChapterExcludeChanged(String),
+    AllowedExtensionsChanged(String),
+    DeniedExtensionsChanged(String),
+    PlayerCommandChanged(String),
+    SubtitleArgChanged(String),
+    AudioArgChanged(String),
+    StartArgChanged(String),
+    TrackPlayersToggled(bool),
+    MaxEntrySizeChanged(String),
+    MaxCacheSizeChanged(String),
+    RemoteApiToggled(bool),
+    RemoteAddressChanged(String),
+    RemoteTokenChanged(String),
+    ImportKodi,
+}
+
+pub struct SettingsPage {
+    chapter_include: String,
+    chapter_exclude: String,
+    allowed_extensions: String,
+    denied_extensions: String,
+    player: Player,
+    track_players: bool,
+    archive_limits: ArchiveLimits,
+    max_entry_size: String,
+    max_cache_size: String,
+    remote_api: RemoteApi,
+    error: Option<ErrorKind>,
+    remote_error: Option<AppError>,
+    kodi_status: Option<String>,
+}
+
+impl SettingsPage {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            chapter_include: join_patterns(&settings.chapter_filter.include),
+            chapter_exclude: join_patterns(&settings.chapter_filter.exclude),
+            allowed_extensions: join_patterns(&settings.media_extensions.allow),
+            denied_extensions: join_patterns(&settings.media_extensions.deny),
+            player: settings.player.clone(),
+            track_players: settings.track_players,
+            archive_limits: settings.archive_limits.clone(),
+            max_entry_size: settings.archive_limits.max_entry_size.to_string(),
+            max_cache_size: settings.archive_limits.max_cache_size.to_string(),
+            remote_api: settings.remote_api.clone(),
+            error: None,
+            remote_error: None,
+            kodi_status: None,
+        }
+    }
+
+    /// Show why the API server failed to start, `None` hides the error
+    pub fn set_remote_error(&mut self, error: Option<AppError>) {
+        self.remote_error = error;
+    }
+
+    /// Show the result of the import from Kodi
+    pub fn set_kodi_status(&mut self, status: impl Into<String>) {
+        self.kodi_status = Some(status.into());
+    }
+
+    /// Apply changes to the `settings`. Returns `true` if settings was changed
+    pub fn update(&mut self, message: Msg, settings: &mut Settings) -> bool {
+        match message {
+            Msg::ChapterIncludeChanged(value) => self.chapter_include = value,
+            Msg::ChapterExcludeChanged(value) => self.chapter_exclude = value,
+            Msg::AllowedExtensionsChanged(value) => self.allowed_extensions = value,
+            Msg::DeniedExtensionsChanged(value) => self.denied_extensions = value,
+            Msg::PlayerCommandChanged(value) => self.player.command = value,
+            Msg::SubtitleArgChanged(value) => self.player.subtitle_arg = value,
+            Msg::AudioArgChanged(value) => self.player.audio_arg = value,
+            Msg::StartArgChanged(value) => self.player.start_arg = value,
+            Msg::TrackPlayersToggled(value) => self.track_players = value,
+            Msg::MaxEntrySizeChanged(value) => {
+                if let Ok(size) = value.trim().parse() {
+                    self.archive_limits.max_entry_size = size;
+                }
+                self.max_entry_size = value;
+            }
+            Msg::MaxCacheSizeChanged(value) => {
+                if let Ok(size) = value.trim().parse() {
+                    self.archive_limits.max_cache_size = size;
+                }
+                self.max_cache_size = value;
+            }
+            Msg::RemoteApiToggled(value) => self.remote_api.enabled = value,
+            Msg::RemoteAddressChanged(value) => self.remote_api.address = value,
+            Msg::RemoteTokenChanged(value) => self.remote_api.token = value,
+            Msg::Back | Msg::ImportKodi => return false,
+        }
+        let chapter_filter = ChapterFilter {
+            include: split_patterns(&self.chapter_include),
+            exclude: split_patterns(&self.chapter_exclude),
+        };
+        if let Err(err) = chapter_filter.validate() {
+            self.error = Some(err);
+            return false;
+        }
+        self.error = None;
+        let media_extensions = MediaExtensions {
+            allow: split_patterns(&self.allowed_extensions),
+            deny: split_patterns(&self.denied_extensions),
+        };
+        let new_settings = Settings {
+            chapter_filter,
+            media_extensions,
+            player: self.player.clone(),
+            archive_limits: self.archive_limits.clone(),
+            remote_api: self.remote_api.clone(),
+            track_players: self.track_players,
+            poster_grid: settings.poster_grid,
+        };
+        if *settings == new_settings {
+            return false;
+        }
+        *settings = new_settings;
+        true
+    }
+}
+
+impl Page for SettingsPage {
+    type Message = Msg;
+
+    fn view(&self) -> Element<'_, Msg> {
+        let spacing = theme::spacing();
+
+        let top = row![
+            container(
+                button::text("Back")
+                    .leading_icon(gui::icon::back())
+                    .on_press(Msg::Back)
+            )
+            .width(Length::Fill),
+            text::title4("Settings"),
+            horizontal_space(),
+        ]
+        .align_y(Alignment::Center);
+
+        let chapter_filter = Column::new()
+            .push(text::heading("Chapter folders"))
+            .push(signed_text_input(
+                "Include",
+                &self.chapter_include,
+                Msg::ChapterIncludeChanged,
+            ))
+            .push(divider::horizontal::default())
+            .push(signed_text_input(
+                "Exclude",
+                &self.chapter_exclude,
+                Msg::ChapterExcludeChanged,
+            ))
+            .push(hint(
+                "Comma separated glob patterns. Prefix \"re:\" marks regular expression",
+            ))
+            .push_maybe(self.error.as_ref().map(|err| text(err.to_string())))
+            .spacing(spacing.space_xs);
+
+        let media_extensions = Column::new()
+            .push(text::heading("Media files"))
+            .push(signed_text_input(
+                "Always media",
+                &self.allowed_extensions,
+                Msg::AllowedExtensionsChanged,
+            ))
+            .push(divider::horizontal::default())
+            .push(signed_text_input(
+                "Never media",
+                &self.denied_extensions,
+                Msg::DeniedExtensionsChanged,
+            ))
+            .push(hint(
+                "Comma separated file extensions, for example \"m4b, opus\". \
+                Files with unknown extensions are recognized by their content",
+            ))
+            .spacing(spacing.space_xs);
+
+        let player = Column::new()
+            .push(text::heading("Player"))
+            .push(signed_text_input(
+                "Command",
+                &self.player.command,
+                Msg::PlayerCommandChanged,
+            ))
+            .push(divider::horizontal::default())
+            .push(signed_text_input(
+                "Subtitle argument",
+                &self.player.subtitle_arg,
+                Msg::SubtitleArgChanged,
+            ))
+            .push(divider::horizontal::default())
+            .push(signed_text_input(
+                "Audio track argument",
+                &self.player.audio_arg,
+                Msg::AudioArgChanged,
+            ))
+            .push(divider::horizontal::default())
+            .push(signed_text_input(
+                "Start position argument",
+                &self.player.start_arg,
+                Msg::StartArgChanged,
+            ))
+            .push(hint(
+                "For example \"mpv --fs --title='My show'\", arguments are quoted like in a shell. \
+                Leave the command empty to use the default program, subtitles, audio tracks \
+                and chapter positions are not passed to it and a warning is shown",
+            ))
+            .spacing(spacing.space_xs);
+
+        let track_players = Column::new()
+            .push(
+                row![
+                    text::heading("Follow other players"),
+                    horizontal_space(),
+                    toggler(self.track_players).on_toggle(Msg::TrackPlayersToggled),
+                ]
+                .align_y(Alignment::Center),
+            )
+            .push(hint(
+                "Episode and position of the media are updated from files played \
+                in any player supporting MPRIS",
+            ))
+            .spacing(spacing.space_xs);
+
+        let archives = Column::new()
+            .push(text::heading("Archives"))
+            .push(signed_text_input(
+                "Largest episode, MiB",
+                &self.max_entry_size,
+                Msg::MaxEntrySizeChanged,
+            ))
+            .push(divider::horizontal::default())
+            .push(signed_text_input(
+                "Cache size, MiB",
+                &self.max_cache_size,
+                Msg::MaxCacheSizeChanged,
+            ))
+            .push(hint(
+                "Episodes are extracted from ZIP archives before watching. \
+                Least recently watched files are removed when the cache is full",
+            ))
+            .spacing(spacing.space_xs);
+
+        let remote_api = Column::new()
+            .push(
+                row![
+                    text::heading("Remote control"),
+                    horizontal_space(),
+                    toggler(self.remote_api.enabled).on_toggle(Msg::RemoteApiToggled),
+                ]
+                .align_y(Alignment::Center),
+            )
+            .push(signed_text_input(
+                "Address",
+                &self.remote_api.address,
+                Msg::RemoteAddressChanged,
+            ))
+            .push(divider::horizontal::default())
+            .push(signed_text_input(
+                "Token",
+                &self.remote_api.token,
+                Msg::RemoteTokenChanged,
+            ))
+            .push(hint(
+                "Local HTTP server for scripts and phone shortcuts. \
+                Requests must have \"Authorization: Bearer <token>\" header",
+            ))
+            .push_maybe(self.remote_error.as_ref().map(|err| text(err.to_string())))
+            .spacing(spacing.space_xs);
+
+        let kodi = Column::new()
+            .push(
+                row![
+                    text::heading("Kodi"),
+                    horizontal_space(),
+                    button::standard("Import").on_press(Msg::ImportKodi),
+                ]
+                .align_y(Alignment::Center),
+            )
+            .push(hint(
+                "Create or update media from the last played episodes of TV shows \
+                in the Kodi video database",
+            ))
+            .push_maybe(self.kodi_status.as_deref().map(text))
+            .spacing(spacing.space_xs);
+
+        let cards = Column::new()
+            .push(card(chapter_filter))
+            .push(card(media_extensions))
+            .push(card(player))
+            .push(card(track_players))
+            .push(card(archives))
+            .push(card(remote_api))
+            .push(card(kodi))
+            .spacing(spacing.space_xs);
+
+        Column::new()
+            .push(top)
+            .push(scrollable(cards).height(Length::Fill))
+            .padding(spacing.space_xs)
+            .spacing(spacing.space_xs)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+fn card<'a>(content: Column<'a, Msg>) -> Element<'a, Msg> {
+    container(content)
+        .padding(theme::spacing().space_xs)
+        .class(style::Container::Card)
+        .into()
+}
+
+fn hint(content: &str) -> Element<'_, Msg> {
+    text(content).font(font::light()).size(13).into()
+}
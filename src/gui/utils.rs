@@ -46,3 +46,18 @@ where
                 .into(),
         )
 }
+
+/// Split comma separated patterns entered by the user
+pub fn split_patterns(patterns: &str) -> Vec<String> {
+    patterns
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Show patterns in a text input as comma separated
+pub fn join_patterns(patterns: &[String]) -> String {
+    patterns.join(", ")
+}
@@ -4,18 +4,38 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+mod actions;
+mod api;
+mod cli;
+mod error;
 mod gui;
-mod model;
-mod utils;
+mod mpris;
+mod report;
+mod tui;
 
+use std::process::ExitCode;
+
+use clap::Parser;
 use cosmic::app::Settings;
 use cosmic::iced::Size;
 
+use cli::Cli;
 use gui::app::OpenMediaTracker;
 use log::LevelFilter;
 
-fn main() -> cosmic::iced::Result {
+fn main() -> ExitCode {
     env_logger::builder().filter_level(LevelFilter::Warn).init();
 
-    cosmic::app::run::<OpenMediaTracker>(Settings::default().size(Size::new(600.0, 500.0)), ())
+    let cli = Cli::parse();
+    if let Some(command) = cli.command {
+        return cli::run(command, cli.json);
+    }
+    let settings = Settings::default().size(Size::new(600.0, 500.0));
+    match cosmic::app::run::<OpenMediaTracker>(settings, ()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -1,19 +0,0 @@
-use crate::model::Placeholder;
-
-#[derive(Debug, Clone)]
-pub struct MaybeError<T, E> {
-    pub value: T,
-    pub error: Option<E>,
-}
-
-impl<T, E> MaybeError<T, E>
-where
-    T: Placeholder,
-{
-    pub fn error(err: E) -> Self {
-        Self {
-            value: T::placeholder(),
-            error: Some(err),
-        }
-    }
-}
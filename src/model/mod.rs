@@ -1,28 +0,0 @@
-/*
- * This Source Code Form is subject to the terms of the Mozilla Public
- * License, v. 2.0. If a copy of the MPL was not distributed with this
- * file, You can obtain one at https://mozilla.org/MPL/2.0/.
- */
-
-mod config;
-mod episode;
-mod episodes;
-mod error;
-mod loaded_data;
-mod loading;
-mod maybe_error;
-mod media;
-mod media_handler;
-mod media_list;
-mod placeholder;
-
-pub use config::Config;
-pub use episode::Episode;
-pub use episodes::Episodes;
-pub use error::{Error, ErrorKind, Result};
-pub use loaded_data::LoadedData;
-pub use loading::LoadingQueue;
-pub use maybe_error::MaybeError;
-pub use media_handler::MediaHandler;
-pub use media_list::{MediaList, MediaListRef, MediaListRefMut};
-pub use placeholder::Placeholder;
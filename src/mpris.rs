@@ -0,0 +1,272 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Progress from any media player which supports MPRIS. Players on the
+//! session bus are polled, files they play are matched with episodes of the
+//! known media
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use open_media_tracker::{MediaHandler, MediaList, Result, Settings};
+use tokio::sync::mpsc;
+use url::Url;
+use zbus::fdo::DBusProxy;
+use zbus::names::OwnedBusName;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::OwnedValue;
+use zbus::{Connection, proxy};
+
+use crate::error::{AppError, AppResult};
+
+const BUS_NAME_PREFIX: &str = "org.mpris.MediaPlayer2.";
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Position is reported less often than players are polled
+const POSITION_STEP: Duration = Duration::from_secs(15);
+const PLAYING_BUFFER: usize = 16;
+
+#[proxy(
+    interface = "org.mpris.MediaPlayer2.Player",
+    default_path = "/org/mpris/MediaPlayer2"
+)]
+trait Player {
+    #[zbus(property)]
+    fn playback_status(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn metadata(&self) -> zbus::Result<HashMap<String, OwnedValue>>;
+
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn position(&self) -> zbus::Result<i64>;
+}
+
+/// Local file playing in one of the players
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Playing {
+    pub path: PathBuf,
+    pub position: Duration,
+}
+
+/// Start polling players in the background. A file is reported when it
+/// starts playing and then as its position moves on. Polling stops once the
+/// receiver is dropped
+pub async fn spawn() -> AppResult<mpsc::Receiver<Playing>> {
+    let connection = Connection::session()
+        .await
+        .map_err(|err| AppError::track_players(err.to_string()))?;
+    let (sender, receiver) = mpsc::channel(PLAYING_BUFFER);
+    tokio::spawn(poll(connection, sender));
+    Ok(receiver)
+}
+
+async fn poll(connection: Connection, sender: mpsc::Sender<Playing>) {
+    let mut reported: HashMap<OwnedBusName, Playing> = HashMap::new();
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    while !sender.is_closed() {
+        interval.tick().await;
+        let players = match players(&connection).await {
+            Ok(players) => players,
+            Err(err) => {
+                log::warn!("MPRIS: {err}");
+                continue;
+            }
+        };
+        reported.retain(|name, _| players.contains(name));
+        for name in players {
+            let playing = match playing(&connection, &name).await {
+                Ok(Some(playing)) => playing,
+                Ok(None) => continue,
+                Err(err) => {
+                    log::debug!("MPRIS: {name}: {err}");
+                    continue;
+                }
+            };
+            let moved_on = reported.get(&name).is_none_or(|last| {
+                last.path != playing.path
+                    || last.position.abs_diff(playing.position) >= POSITION_STEP
+            });
+            if !moved_on {
+                continue;
+            }
+            reported.insert(name, playing.clone());
+            if sender.send(playing).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+async fn players(connection: &Connection) -> zbus::Result<Vec<OwnedBusName>> {
+    let names = DBusProxy::new(connection).await?.list_names().await?;
+    let players = names
+        .into_iter()
+        .filter(|name| name.starts_with(BUS_NAME_PREFIX))
+        .collect();
+    Ok(players)
+}
+
+async fn playing(connection: &Connection, name: &OwnedBusName) -> zbus::Result<Option<Playing>> {
+    let player = PlayerProxy::builder(connection)
+        .destination(name.as_ref())?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await?;
+    if player.playback_status().await? != "Playing" {
+        return Ok(None);
+    }
+    let metadata = player.metadata().await?;
+    let Some(path) = metadata.get("xesam:url").and_then(file_path) else {
+        return Ok(None);
+    };
+    // Some players don't know the position
+    let position = player
+        .position()
+        .await
+        .ok()
+        .and_then(|micros| u64::try_from(micros).ok())
+        .map(Duration::from_micros)
+        .unwrap_or_default();
+    Ok(Some(Playing { path, position }))
+}
+
+fn file_path(url: &OwnedValue) -> Option<PathBuf> {
+    let url = <&str>::try_from(url).ok()?;
+    Url::parse(url).ok()?.to_file_path().ok()
+}
+
+/// Media which may have the file as an episode: the file is inside the
+/// chapter directory or the chapter is a playlist
+pub fn candidates(media_list: &MediaList, path: &Path) -> Vec<usize> {
+    media_list
+        .iter()
+        .enumerate()
+        .filter(|(_, media)| media.may_contain(path))
+        .map(|(id, _)| id)
+        .collect()
+}
+
+/// Move the media to the playing episode and remember the position. Returns
+/// `false` if the file is not an episode of the media
+pub async fn follow(
+    media: &mut MediaHandler,
+    settings: &Settings,
+    playing: &Playing,
+) -> Result<bool> {
+    let episodes = media.episode_list(settings).await?;
+    let Some((number, episode)) = episodes.find_playing(&playing.path, playing.position) else {
+        return Ok(false);
+    };
+    if number != media.episode() {
+        media.set_episode(number)?;
+    }
+    media.set_episode_anchor(episode)?;
+    let start = episode.start().unwrap_or_default();
+    media.set_position(Some(playing.position.saturating_sub(start)))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+
+    use zbus::connection::Builder;
+    use zbus::interface;
+    use zbus::zvariant::Value;
+
+    use super::*;
+
+    /// Private session bus which is stopped when the test ends
+    struct Bus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl Bus {
+        /// `None` if `dbus-daemon` is not installed or fails to start
+        fn start() -> Option<Self> {
+            let mut daemon = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok()?;
+            let stdout = daemon.stdout.take();
+            let mut bus = Self {
+                daemon,
+                address: String::new(),
+            };
+            BufReader::new(stdout?).read_line(&mut bus.address).ok()?;
+            bus.address = bus.address.trim().to_owned();
+            (!bus.address.is_empty()).then_some(bus)
+        }
+    }
+
+    impl Drop for Bus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    struct FakePlayer {
+        status: &'static str,
+        url: String,
+        position: Duration,
+    }
+
+    #[interface(name = "org.mpris.MediaPlayer2.Player")]
+    impl FakePlayer {
+        #[zbus(property)]
+        fn playback_status(&self) -> String {
+            self.status.to_owned()
+        }
+
+        #[zbus(property)]
+        fn metadata(&self) -> HashMap<String, OwnedValue> {
+            let url = OwnedValue::try_from(Value::from(self.url.as_str())).unwrap();
+            HashMap::from([("xesam:url".to_owned(), url)])
+        }
+
+        #[zbus(property)]
+        fn position(&self) -> i64 {
+            i64::try_from(self.position.as_micros()).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_file_playing_on_private_bus() {
+        let Some(bus) = Bus::start() else {
+            eprintln!("Skipped: dbus-daemon can't be started");
+            return;
+        };
+        let address = bus.address.as_str();
+
+        let path = PathBuf::from("/videos/show/01.mkv");
+        let position = Duration::from_secs(90);
+        let player = FakePlayer {
+            status: "Playing",
+            url: Url::from_file_path(&path).unwrap().to_string(),
+            position,
+        };
+        let _player = Builder::address(address)
+            .unwrap()
+            .name("org.mpris.MediaPlayer2.fake")
+            .unwrap()
+            .serve_at("/org/mpris/MediaPlayer2", player)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let connection = Builder::address(address).unwrap().build().await.unwrap();
+
+        let names = players(&connection).await.unwrap();
+        assert_eq!(names.len(), 1);
+        let found = playing(&connection, &names[0]).await.unwrap();
+        assert_eq!(found, Some(Playing { path, position }));
+    }
+}
@@ -0,0 +1,184 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::PathBuf;
+
+use open_media_tracker::utils::format_duration;
+use open_media_tracker::{Episode, EpisodeList, MediaHandler, Metadata, ResumePoint};
+use serde::Serialize;
+
+/// Result of the command
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Report {
+    List(Vec<MediaReport>),
+    Media(Box<MediaReport>),
+    Resume(Vec<ResumeReport>),
+}
+
+impl Report {
+    /// Print as pretty JSON or as lines of text
+    pub fn print(&self, json: bool) {
+        if json {
+            match serde_json::to_string_pretty(self) {
+                Ok(json) => println!("{json}"),
+                Err(err) => eprintln!("Failed to serialize the result: {err}"),
+            }
+            return;
+        }
+        match self {
+            Self::List(media_list) => {
+                for media in media_list {
+                    println!("{}", media.summary());
+                }
+            }
+            Self::Media(media) => media.print_details(),
+            Self::Resume(points) => {
+                for point in points {
+                    println!("{}", point.summary());
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MediaReport {
+    name: String,
+    chapter: u8,
+    episode: u8,
+    chapter_path: PathBuf,
+    series_path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    episodes_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_episode: Option<EpisodeReport>,
+    /// Seconds from the beginning of the current episode where playback
+    /// stopped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    position_secs: Option<u64>,
+    /// Seconds from the beginning of the current episode to the end of the
+    /// chapter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remaining_secs: Option<u64>,
+    /// Details of the show from NFO files and the folder artwork
+    #[serde(skip_serializing_if = "Metadata::is_empty")]
+    metadata: Metadata,
+    /// Image chosen instead of the found cover
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cover: Option<PathBuf>,
+}
+
+impl MediaReport {
+    pub fn new(media: &MediaHandler) -> Self {
+        Self::with_episodes(media, None)
+    }
+
+    pub fn with_episodes(media: &MediaHandler, episodes: Option<&EpisodeList>) -> Self {
+        let episode = media.episode();
+        Self {
+            name: media.name().to_string(),
+            chapter: media.chapter(),
+            episode,
+            chapter_path: media.chapter_path().to_path_buf(),
+            series_path: media.series_path().map(PathBuf::from),
+            episodes_count: episodes.and_then(EpisodeList::last_number),
+            current_episode: episodes
+                .and_then(|episodes| episodes.find(episode))
+                .map(EpisodeReport::new),
+            position_secs: media.position().map(|position| position.as_secs()),
+            remaining_secs: episodes
+                .and_then(|episodes| episodes.remaining_duration(episode))
+                .map(|duration| duration.as_secs()),
+            metadata: media.metadata().clone(),
+            cover: media.cover().map(PathBuf::from),
+        }
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "{}: chapter {}, episode {}",
+            self.name, self.chapter, self.episode
+        )
+    }
+
+    fn print_details(&self) {
+        println!("{}", self.name);
+        match (&self.metadata.title, self.metadata.year) {
+            (Some(title), Some(year)) => println!("{title} ({year})"),
+            (Some(title), None) => println!("{title}"),
+            _ => {}
+        }
+        match self.episodes_count {
+            Some(count) => println!(
+                "Chapter {}, episode {} of {count}",
+                self.chapter, self.episode
+            ),
+            None => println!("Chapter {}, episode {}", self.chapter, self.episode),
+        }
+        if let Some(episode) = &self.current_episode {
+            println!("Current episode: {}", episode.name);
+        }
+        if let Some(position_secs) = self.position_secs {
+            let position = std::time::Duration::from_secs(position_secs);
+            println!("Stopped at: {}", format_duration(position));
+        }
+        if let Some(remaining_secs) = self.remaining_secs {
+            let remaining = std::time::Duration::from_secs(remaining_secs);
+            println!("Left in chapter: {}", format_duration(remaining));
+        }
+        println!("Chapter path: {}", self.chapter_path.display());
+        if let Some(series_path) = &self.series_path {
+            println!("Series path: {}", series_path.display());
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct EpisodeReport {
+    name: String,
+    path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_secs: Option<u64>,
+}
+
+impl EpisodeReport {
+    fn new(episode: &Episode) -> Self {
+        Self {
+            name: episode.name().into_owned(),
+            path: episode.path().to_path_buf(),
+            duration_secs: episode.duration().map(|duration| duration.as_secs()),
+        }
+    }
+}
+
+/// Episode and position where another player stopped
+#[derive(Debug, Serialize)]
+pub struct ResumeReport {
+    name: String,
+    episode: u8,
+    position_secs: u64,
+}
+
+impl ResumeReport {
+    pub fn new(name: &str, point: &ResumePoint) -> Self {
+        Self {
+            name: name.to_string(),
+            episode: point.episode,
+            position_secs: point.position.as_secs(),
+        }
+    }
+
+    fn summary(&self) -> String {
+        let position = std::time::Duration::from_secs(self.position_secs);
+        format!(
+            "{}: episode {} at {}",
+            self.name,
+            self.episode,
+            format_duration(position)
+        )
+    }
+}
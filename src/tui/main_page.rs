@@ -0,0 +1,156 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use open_media_tracker::{MediaHandler, MediaListRef, MediaListRefMut};
+use ratatui::Frame;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListState};
+
+use crate::tui::Action;
+
+const HELP: &str = "↑/↓ select · enter open · / search · s sort · q quit";
+const SEARCH_HELP: &str = "type to search · enter done · esc clear";
+
+/// List of media with the search, like the main page of the GUI
+pub struct MainPage {
+    /// Indices of the shown media in the media list
+    shown: Vec<usize>,
+    list_state: ListState,
+    search: String,
+    searching: bool,
+    /// Alphabetical order is reversed
+    reverse: Option<bool>,
+}
+
+impl MainPage {
+    pub fn new(media_list: MediaListRef) -> Self {
+        let mut page = Self {
+            shown: Vec::new(),
+            list_state: ListState::default(),
+            search: String::new(),
+            searching: false,
+            reverse: None,
+        };
+        page.filter(media_list);
+        page
+    }
+
+    pub fn update(&mut self, key: KeyEvent, media_list: MediaListRefMut) -> Action {
+        if self.searching {
+            match key.code {
+                KeyCode::Char(c) => self.search.push(c),
+                KeyCode::Backspace => {
+                    self.search.pop();
+                }
+                KeyCode::Esc => {
+                    self.search.clear();
+                    self.searching = false;
+                }
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Up | KeyCode::Down => return self.select(key.code),
+                _ => return Action::None,
+            }
+            self.filter(media_list);
+            return Action::None;
+        }
+        match key.code {
+            KeyCode::Up | KeyCode::Down | KeyCode::Char('k' | 'j') => self.select(key.code),
+            KeyCode::Enter => self
+                .list_state
+                .selected()
+                .and_then(|index| self.shown.get(index))
+                .map_or(Action::None, |&id| Action::Open(id)),
+            KeyCode::Char('/') => {
+                self.searching = true;
+                Action::None
+            }
+            KeyCode::Char('s') => {
+                self.sort(media_list);
+                Action::None
+            }
+            KeyCode::Esc if !self.search.is_empty() => {
+                self.search.clear();
+                self.filter(media_list);
+                Action::None
+            }
+            KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+            _ => Action::None,
+        }
+    }
+
+    /// Render the page and return the key hints
+    pub fn view(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        media_list: MediaListRef,
+    ) -> &'static str {
+        let [search_area, list_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+        let search = Line::from(format!("Search: {}", self.search));
+        let search = if self.searching {
+            search.bold()
+        } else {
+            search.dim()
+        };
+        frame.render_widget(search, search_area);
+
+        let names = self.shown.iter().map(|&id| media_list[id].name());
+        let list = List::new(names)
+            .block(Block::bordered().title(" Media "))
+            .highlight_style(Style::new().reversed())
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        if self.searching { SEARCH_HELP } else { HELP }
+    }
+
+    fn select(&mut self, code: KeyCode) -> Action {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.list_state.select_previous(),
+            _ => self.list_state.select_next(),
+        }
+        Action::None
+    }
+
+    /// Toggle reversed alphabetical order of the media list
+    fn sort(&mut self, media_list: MediaListRefMut) {
+        let reverse = !self.reverse.unwrap_or_default();
+        self.reverse = Some(reverse);
+        media_list.sort_by(|a, b| a.name().cmp(b.name()));
+        if reverse {
+            media_list.reverse();
+        }
+        self.filter(media_list);
+    }
+
+    /// Show media matching the search, the best matches first
+    fn filter(&mut self, media_list: MediaListRef) {
+        if self.search.is_empty() {
+            self.shown = (0..media_list.len()).collect();
+        } else {
+            let matcher = SkimMatcherV2::default();
+            let mut search_result: Vec<(usize, i64)> = media_list
+                .iter()
+                .map(MediaHandler::name)
+                .enumerate()
+                .filter_map(|(id, name)| {
+                    let score = matcher.fuzzy_match(name, &self.search);
+                    score.map(|score| (id, score))
+                })
+                .collect();
+            search_result.sort_by(|(_, score_a), (_, score_b)| score_b.cmp(score_a));
+            self.shown = search_result.into_iter().map(|(id, _)| id).collect();
+        }
+        let selected = (!self.shown.is_empty()).then_some(0);
+        self.list_state.select(selected);
+    }
+}
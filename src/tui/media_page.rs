@@ -0,0 +1,498 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::PathBuf;
+
+use derive_more::Display;
+use open_media_tracker::utils::format_duration;
+use open_media_tracker::{
+    Chapter, Config, Episode, EpisodeList, ErrorKind, MediaHandler, MediaListRef, MediaListRefMut,
+    PlayWarning, Reconciliation, Result, Settings, SidecarKind, chapter_number, extract,
+};
+use ratatui::Frame;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Clear, Paragraph, Wrap};
+
+use crate::tui::Action;
+
+const HELP: &str = "←/→ episode · [/] chapter · w watch · s skipped files · esc back";
+const CONFIRM_HELP: &str = "y confirm · n cancel";
+
+#[derive(Clone, Display)]
+enum ConfirmKind {
+    #[display("Proposed path to next chapter: {path:?}")]
+    SwitchToNextChapter { path: PathBuf },
+    #[display(
+        "Proposed path to previous chapter: {path:?}. \
+        Episode will be set to the last one in it"
+    )]
+    SwitchToPrevChapter { path: PathBuf },
+    #[display(
+        "Seems like {episodes_on_disk} episode is a last of it chapter. \
+        Switch to the next chapter?"
+    )]
+    EpisodesOverflow { episodes_on_disk: usize },
+}
+
+/// Current episode of the media with the next chapter and the overflow
+/// confirmations, like the media page of the GUI
+pub struct MediaPage {
+    id: usize,
+    episodes: Result<EpisodeList>,
+    chapters: Result<Vec<Chapter>>,
+    confirm: Option<ConfirmKind>,
+    warning: Option<String>,
+    show_skipped: bool,
+}
+
+impl MediaPage {
+    pub async fn new(
+        media_list: MediaListRefMut<'_>,
+        id: usize,
+        settings: &Settings,
+    ) -> Result<Self> {
+        let media = &media_list[id];
+        let mut page = Self {
+            id,
+            episodes: media.episode_list(settings).await,
+            chapters: media.chapter_list(settings).await,
+            confirm: None,
+            warning: None,
+            show_skipped: false,
+        };
+        page.reconcile_episode(media_list)?;
+        page.reconcile_chapter(media_list, settings).await?;
+        Ok(page)
+    }
+
+    pub async fn update(
+        &mut self,
+        key: KeyEvent,
+        media_list: MediaListRefMut<'_>,
+        settings: &Settings,
+        config: &Config,
+    ) -> Result<Action> {
+        if let Some(kind) = self.confirm.clone() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    self.confirm = None;
+                    self.confirm_kind_update(media_list, settings, kind).await?;
+                }
+                KeyCode::Char('n') | KeyCode::Esc => self.confirm = None,
+                _ => {}
+            }
+            return Ok(Action::None);
+        }
+        let media = &media_list[self.id];
+        let (chapter, episode) = (media.chapter(), media.episode());
+        match key.code {
+            KeyCode::Right | KeyCode::Char('l' | '+') => {
+                self.set_episode(media_list, settings, episode.saturating_add(1))
+                    .await?;
+            }
+            KeyCode::Left | KeyCode::Char('h' | '-') if episode > 1 => {
+                self.set_episode(media_list, settings, episode - 1).await?;
+            }
+            KeyCode::Char(']') => {
+                self.set_chapter(media_list, settings, chapter.saturating_add(1))
+                    .await?;
+            }
+            KeyCode::Char('[') if chapter > 1 => {
+                self.set_chapter(media_list, settings, chapter - 1).await?;
+            }
+            KeyCode::Char('w') | KeyCode::Enter => {
+                if let Some(warning) = self.watch(media_list, settings, config).await? {
+                    self.warning = Some(warning.to_string());
+                }
+            }
+            KeyCode::Char('s') => self.show_skipped = !self.show_skipped,
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Backspace => return Ok(Action::Back),
+            _ => {}
+        }
+        Ok(Action::None)
+    }
+
+    /// Render the page and return the key hints
+    pub fn view(&self, frame: &mut Frame, area: Rect, media_list: MediaListRef) -> &'static str {
+        let media = &media_list[self.id];
+        let mut lines = vec![
+            Line::from(self.chapter_line(media)),
+            Line::from(self.episode_line(media)),
+            Line::default(),
+        ];
+        lines.extend(self.watch_sign(media).map(|sign| Line::from(sign).bold()));
+        lines.extend(self.sidecars_sign(media).map(Line::from));
+        lines.extend(self.remaining_sign(media).map(Line::from));
+        if let Ok(episodes) = &self.episodes
+            && !episodes.skipped().is_empty()
+        {
+            lines.push(Line::from(format!("{} files skipped", episodes.skipped().len())).dim());
+            if self.show_skipped {
+                let skipped = episodes.skipped().iter();
+                lines.extend(skipped.map(|file| Line::from(format!("  {file}")).dim()));
+            }
+        }
+        lines.extend(
+            self.warning
+                .as_deref()
+                .map(|warning| Line::from(warning).yellow()),
+        );
+        lines.push(Line::default());
+        lines.push(Line::from(format!(
+            "Chapter path: {}",
+            media.chapter_path().display()
+        )));
+        if let Some(series_path) = media.series_path() {
+            lines.push(Line::from(format!(
+                "Series path: {}",
+                series_path.display()
+            )));
+        }
+        let block = Block::bordered().title(format!(" {} ", media.name()));
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, area);
+
+        let Some(kind) = &self.confirm else {
+            return HELP;
+        };
+        let popup_area = area.centered(Constraint::Percentage(70), Constraint::Length(6));
+        let popup = Paragraph::new(kind.to_string())
+            .block(Block::bordered().title(" Confirm "))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(popup, popup_area);
+        CONFIRM_HELP
+    }
+
+    async fn confirm_kind_update(
+        &mut self,
+        media_list: MediaListRefMut<'_>,
+        settings: &Settings,
+        kind: ConfirmKind,
+    ) -> Result<()> {
+        match kind {
+            ConfirmKind::SwitchToNextChapter { path } => {
+                self.set_chapter_path_from_start(media_list, settings, path)
+                    .await
+            }
+            ConfirmKind::SwitchToPrevChapter { path } => {
+                self.set_chapter_path_from_end(media_list, settings, path)
+                    .await
+            }
+            ConfirmKind::EpisodesOverflow { .. } => {
+                self.increase_chapter(media_list, settings).await
+            }
+        }
+    }
+
+    fn episode<'a>(&'a self, media: &MediaHandler) -> Option<&'a Episode> {
+        self.episodes.as_ref().ok()?.find(media.episode())
+    }
+
+    fn chapter(&self, number: u8) -> Option<&Chapter> {
+        let chapters = self.chapters.as_ref().ok()?;
+        chapters.get(usize::from(number).checked_sub(1)?)
+    }
+
+    fn chapter_line(&self, media: &MediaHandler) -> String {
+        let number = media.chapter();
+        self.chapter(number).map_or_else(
+            || format!("Chapter {number}"),
+            |chapter| {
+                format!(
+                    "Chapter {number}: {} ({} episodes)",
+                    chapter.name(),
+                    chapter.episodes_count()
+                )
+            },
+        )
+    }
+
+    fn episode_line(&self, media: &MediaHandler) -> String {
+        let number = media.episode();
+        let last_number = self
+            .episodes
+            .as_ref()
+            .ok()
+            .and_then(EpisodeList::last_number);
+        last_number.map_or_else(
+            || format!("Episode {number}"),
+            |last_number| format!("Episode {number} of {last_number}"),
+        )
+    }
+
+    fn watch_sign(&self, media: &MediaHandler) -> Option<String> {
+        if media.chapter_path().as_os_str().is_empty() {
+            return None;
+        }
+        let episodes = match &self.episodes {
+            Ok(episodes) => episodes,
+            Err(err) => return Some(format!("Chapter path is incorrect: {err}")),
+        };
+        let Some(episode) = episodes.find(media.episode()) else {
+            return Some(format!(
+                "Chapter path is incorrect: {}",
+                ErrorKind::EpisodeNotFound
+            ));
+        };
+        let sign = episode.number().map_or_else(
+            || episode.name().into_owned(),
+            |number| format!("{} ({number})", episode.name()),
+        );
+        Some(sign)
+    }
+
+    /// Subtitles and audio tracks which will be passed to the player
+    fn sidecars_sign(&self, media: &MediaHandler) -> Option<String> {
+        let episode = self.episode(media)?;
+        let file_names = |kind| {
+            episode
+                .sidecars()
+                .iter()
+                .filter(|sidecar| sidecar.kind() == kind)
+                .map(|sidecar| sidecar.file_name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let subtitles = file_names(SidecarKind::Subtitle);
+        let audio = file_names(SidecarKind::Audio);
+        let sign = [("Subtitles", subtitles), ("Audio", audio)]
+            .into_iter()
+            .filter(|(_, file_names)| !file_names.is_empty())
+            .map(|(title, file_names)| format!("{title}: {file_names}"))
+            .collect::<Vec<_>>()
+            .join("  ·  ");
+        (!sign.is_empty()).then_some(sign)
+    }
+
+    /// Time left to watch in the chapter and in the whole series
+    fn remaining_sign(&self, media: &MediaHandler) -> Option<String> {
+        let episodes = self.episodes.as_ref().ok()?;
+        let chapter_left = episodes.remaining_duration(media.episode())?;
+        let chapter_sign = format!("{} left in chapter", format_duration(chapter_left));
+        let later_chapters = self
+            .chapters
+            .as_ref()
+            .ok()
+            .filter(|_| self.chapter(media.chapter()).is_some())
+            .map(|chapters| &chapters[usize::from(media.chapter())..]);
+        let Some(later_chapters) = later_chapters else {
+            return Some(chapter_sign);
+        };
+        let series_left = later_chapters
+            .iter()
+            .filter_map(Chapter::duration)
+            .fold(chapter_left, |total, duration| total + duration);
+        Some(format!(
+            "{chapter_sign}  ·  {} left in series",
+            format_duration(series_left)
+        ))
+    }
+
+    /// Correct the episode number if the remembered episode file has moved
+    fn reconcile_episode(&mut self, media_list: MediaListRefMut) -> Result<()> {
+        let Ok(episodes) = &self.episodes else {
+            return Ok(());
+        };
+        let media = &mut media_list[self.id];
+        match media.reconcile_episode(episodes)? {
+            Reconciliation::Unchanged | Reconciliation::Moved { .. } => {}
+            Reconciliation::Missing { file_name } => {
+                self.warning = Some(format!(
+                    "File of the current episode \"{file_name}\" is not found in the chapter path"
+                ));
+                return Ok(());
+            }
+        }
+        self.anchor_episode(media_list)
+    }
+
+    fn anchor_episode(&self, media_list: MediaListRefMut) -> Result<()> {
+        let media = &mut media_list[self.id];
+        if let Some(episode) = self.episode(media) {
+            media.set_episode_anchor(episode)?;
+        }
+        Ok(())
+    }
+
+    /// Bring chapter number and chapter path in accordance with the series
+    async fn reconcile_chapter(
+        &mut self,
+        media_list: MediaListRefMut<'_>,
+        settings: &Settings,
+    ) -> Result<()> {
+        let Ok(chapters) = &self.chapters else {
+            return Ok(());
+        };
+        if chapters.is_empty() {
+            return Ok(());
+        }
+        let media = &mut media_list[self.id];
+        if media.chapter_path().as_os_str().is_empty() {
+            let chapter = media.chapter();
+            return self.set_chapter(media_list, settings, chapter).await;
+        }
+        if let Some(number) = chapter_number(chapters, media.chapter_path())
+            && number != media.chapter()
+        {
+            media.set_chapter(number)?;
+        }
+        Ok(())
+    }
+
+    async fn set_chapter(
+        &mut self,
+        media_list: MediaListRefMut<'_>,
+        settings: &Settings,
+        value: u8,
+    ) -> Result<()> {
+        let media = &mut media_list[self.id];
+        let decreased = value < media.chapter();
+        media.set_chapter(value)?;
+        let Some(chapter_path) = self.chapter(value).map(|c| c.path().to_path_buf()) else {
+            if decreased && !media.chapter_path().as_os_str().is_empty() {
+                match media.prev_chapter_path(settings).await {
+                    Ok(path) => self.confirm = Some(ConfirmKind::SwitchToPrevChapter { path }),
+                    // Decreasing the first chapter is not an error
+                    Err(ErrorKind::FindPrevChapterPath { .. }) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+            return Ok(());
+        };
+        if chapter_path == media.chapter_path() {
+            return Ok(());
+        }
+        if decreased {
+            return self
+                .set_chapter_path_from_end(media_list, settings, chapter_path)
+                .await;
+        }
+        self.set_chapter_path_from_start(media_list, settings, chapter_path)
+            .await
+    }
+
+    /// Set chapter path and move to the first episode in it
+    async fn set_chapter_path_from_start(
+        &mut self,
+        media_list: MediaListRefMut<'_>,
+        settings: &Settings,
+        chapter_path: PathBuf,
+    ) -> Result<()> {
+        let media = &mut media_list[self.id];
+        media.set_chapter_path(chapter_path)?;
+        media.reload_metadata().await?;
+        self.episodes = media.episode_list(settings).await;
+        let first_episode = self.episodes.as_ref().map_or(1, EpisodeList::first_number);
+        media.set_episode(first_episode)?;
+        self.anchor_episode(media_list)
+    }
+
+    /// Set chapter path and move to the last episode in it
+    async fn set_chapter_path_from_end(
+        &mut self,
+        media_list: MediaListRefMut<'_>,
+        settings: &Settings,
+        chapter_path: PathBuf,
+    ) -> Result<()> {
+        let media = &mut media_list[self.id];
+        media.set_chapter_path(chapter_path)?;
+        media.reload_metadata().await?;
+        self.episodes = media.episode_list(settings).await;
+        let last_episode = self
+            .episodes
+            .as_ref()
+            .ok()
+            .and_then(EpisodeList::last_number);
+        let Some(last_episode) = last_episode else {
+            return Ok(());
+        };
+        media.set_episode(u8::try_from(last_episode).unwrap_or(u8::MAX))?;
+        self.anchor_episode(media_list)
+    }
+
+    async fn set_episode(
+        &mut self,
+        media_list: MediaListRefMut<'_>,
+        settings: &Settings,
+        value: u8,
+    ) -> Result<()> {
+        let last_number = self
+            .episodes
+            .as_ref()
+            .ok()
+            .and_then(EpisodeList::last_number);
+        if last_number.is_some_and(|last_number| usize::from(value) > last_number) {
+            // Episodes could be added since the list was read
+            self.episodes = media_list[self.id].episode_list(settings).await;
+            let last_number = self
+                .episodes
+                .as_ref()
+                .ok()
+                .and_then(EpisodeList::last_number);
+            if let Some(last_number) = last_number
+                && usize::from(value) > last_number
+            {
+                let kind = ConfirmKind::EpisodesOverflow {
+                    episodes_on_disk: last_number,
+                };
+                self.confirm = Some(kind);
+                return Ok(());
+            }
+        }
+        media_list[self.id].set_episode(value)?;
+        self.anchor_episode(media_list)
+    }
+
+    async fn increase_chapter(
+        &mut self,
+        media_list: MediaListRefMut<'_>,
+        settings: &Settings,
+    ) -> Result<()> {
+        let media = &mut media_list[self.id];
+        if media.chapter() == 0 {
+            return media.set_chapter(1);
+        }
+        let next_chapter = media.chapter().saturating_add(1);
+        media.set_chapter(next_chapter)?;
+        if let Some(chapter) = self.chapter(next_chapter) {
+            let chapter_path = chapter.path().to_path_buf();
+            return self
+                .set_chapter_path_from_start(media_list, settings, chapter_path)
+                .await;
+        }
+        // Media without episodes counts them by position
+        if media.chapter_path().as_os_str().is_empty() {
+            return media.set_episode(1);
+        }
+        let path = media.next_chapter_path(settings).await?;
+        self.confirm = Some(ConfirmKind::SwitchToNextChapter { path });
+        Ok(())
+    }
+
+    async fn watch(
+        &self,
+        media_list: MediaListRef<'_>,
+        settings: &Settings,
+        config: &Config,
+    ) -> Result<Option<PlayWarning>> {
+        let episode = self
+            .episode(&media_list[self.id])
+            .ok_or(ErrorKind::EpisodeNotFound)?;
+        let Some(entry) = episode.archive_entry().cloned() else {
+            return settings.player.play(episode);
+        };
+        let cache_dir = config.archive_cache.clone();
+        let limits = settings.archive_limits.clone();
+        let path = extract(entry, cache_dir, limits).await?;
+        settings.player.play(&episode.extracted(path))
+    }
+}
@@ -0,0 +1,118 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Terminal frontend with the same pages as the GUI
+
+mod main_page;
+mod media_page;
+
+use std::sync::Arc;
+
+use open_media_tracker::{Config, ErrorKind, MediaList, Result, Settings};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::{DefaultTerminal, Frame};
+
+use main_page::MainPage;
+use media_page::MediaPage;
+
+/// What the page asks the application to do after a key press
+enum Action {
+    None,
+    Open(usize),
+    Back,
+    Quit,
+}
+
+enum Screen {
+    Main(MainPage),
+    Media(MediaPage),
+}
+
+struct App {
+    config: Arc<Config>,
+    settings: Settings,
+    media_list: MediaList,
+    screen: Screen,
+    error: Option<ErrorKind>,
+}
+
+/// Run the TUI until the user quits
+pub async fn run(config: Arc<Config>, settings: Settings, media_list: MediaList) -> Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let screen = Screen::Main(MainPage::new(&media_list));
+    let mut app = App {
+        config,
+        settings,
+        media_list,
+        screen,
+        error: None,
+    };
+    let res = app.run(&mut terminal).await;
+    ratatui::restore();
+    res
+}
+
+impl App {
+    async fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.view(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                return Ok(());
+            }
+            // Any key closes the error
+            if self.error.take().is_some() {
+                continue;
+            }
+            match self.update(key).await {
+                Ok(Action::Quit) => return Ok(()),
+                Ok(_) => {}
+                Err(err) => self.error = Some(err),
+            }
+        }
+    }
+
+    async fn update(&mut self, key: KeyEvent) -> Result<Action> {
+        let action = match &mut self.screen {
+            Screen::Main(page) => page.update(key, &mut self.media_list),
+            Screen::Media(page) => {
+                page.update(key, &mut self.media_list, &self.settings, &self.config)
+                    .await?
+            }
+        };
+        match action {
+            Action::None | Action::Quit => {}
+            Action::Open(id) => {
+                let page = MediaPage::new(&mut self.media_list, id, &self.settings).await?;
+                self.screen = Screen::Media(page);
+            }
+            Action::Back => self.screen = Screen::Main(MainPage::new(&self.media_list)),
+        }
+        Ok(action)
+    }
+
+    fn view(&mut self, frame: &mut Frame) {
+        let [page_area, status_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let help = match &mut self.screen {
+            Screen::Main(page) => page.view(frame, page_area, &self.media_list),
+            Screen::Media(page) => page.view(frame, page_area, &self.media_list),
+        };
+        let status = self.error.as_ref().map_or_else(
+            || Line::from(help).dim(),
+            |err| Line::from(err.to_string()).red(),
+        );
+        frame.render_widget(status, status_area);
+    }
+}
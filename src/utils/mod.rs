@@ -1,13 +0,0 @@
-/*
- * This Source Code Form is subject to the terms of the Mozilla Public
- * License, v. 2.0. If a copy of the MPL was not distributed with this
- * file, You can obtain one at https://mozilla.org/MPL/2.0/.
- */
-
-mod next_dir;
-mod open;
-mod read_dir;
-
-pub use next_dir::next_dir;
-pub use open::{OpenError, open};
-pub use read_dir::{read_dir, read_dir_with_filter};